@@ -0,0 +1,235 @@
+use crate::examples::expected_answers;
+use crate::inputs::{year_dir, InputKind};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// File, inside the year's input directory, holding known answers.
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// What we know about one part of one input: the accepted answer if we have
+/// it, and every guess the site rejected.
+///
+/// ```toml
+/// [day20.real.part1]
+/// too_low = [1467]
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct KnownAnswer {
+    pub answer: Option<toml::Value>,
+    pub too_low: Vec<i64>,
+    pub too_high: Vec<i64>,
+    pub wrong: Vec<toml::Value>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect { expected: String },
+    KnownTooLow,
+    KnownTooHigh,
+    KnownWrong,
+    Unknown,
+}
+
+impl Verdict {
+    /// True if the answer is known to be wrong one way or another.
+    pub fn is_wrong(&self) -> bool {
+        !matches!(self, Verdict::Correct | Verdict::Unknown)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::Incorrect { expected } => write!(f, "WRONG, expected {}", expected),
+            Verdict::KnownTooLow => write!(f, "known too low"),
+            Verdict::KnownTooHigh => write!(f, "known too high"),
+            Verdict::KnownWrong => write!(f, "known wrong"),
+            Verdict::Unknown => write!(f, "unverified"),
+        }
+    }
+}
+
+fn value_to_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+impl KnownAnswer {
+    pub fn check(&self, answer: &str) -> Verdict {
+        if let Some(expected) = &self.answer {
+            let expected = value_to_string(expected);
+            return if expected == answer {
+                Verdict::Correct
+            } else {
+                Verdict::Incorrect { expected }
+            };
+        }
+
+        if self.wrong.iter().any(|w| value_to_string(w) == answer) {
+            return Verdict::KnownWrong;
+        }
+        if let Ok(value) = answer.parse::<i64>() {
+            if self.too_low.iter().any(|&low| value <= low) {
+                return Verdict::KnownTooLow;
+            }
+            if self.too_high.iter().any(|&high| value >= high) {
+                return Verdict::KnownTooHigh;
+            }
+        }
+        Verdict::Unknown
+    }
+}
+
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(PathBuf, io::Error),
+    Toml(PathBuf, toml::de::Error),
+}
+
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnswersError::Io(path, e) => write!(f, "could not read {}: {}", path.display(), e),
+            AnswersError::Toml(path, e) => write!(f, "bad answers file {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for AnswersError {}
+
+/// Known answers keyed by `dayN`, then input key, then `partN`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Answers(BTreeMap<String, BTreeMap<String, BTreeMap<String, KnownAnswer>>>);
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Load answers from `path`. A missing file just means nothing is known.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match read_to_string(path) {
+            Ok(text) => Self::parse(&text).map_err(|e| AnswersError::Toml(path.to_path_buf(), e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(AnswersError::Io(path.to_path_buf(), e)),
+        }
+    }
+
+    /// Answers for the current year, along with those kept beside each
+    /// example. `answers.toml` wins where both have an answer, but an entry
+    /// there without one takes the answer from the sidecar.
+    pub fn load_default() -> Result<Self, AnswersError> {
+        let mut answers = Self::load(&year_dir().join(ANSWERS_FILE))?;
+        for day in crate::DAYS {
+            for (kind, expected) in expected_answers(day.day)? {
+                for part in [1, 2] {
+                    if let Some(known) = expected.part(part) {
+                        answers.add(day.day, &kind, part, known);
+                    }
+                }
+            }
+        }
+        Ok(answers)
+    }
+
+    /// Record `known` unless the answer to that part is already known.
+    fn add(&mut self, day: u32, kind: &InputKind, part: u8, known: KnownAnswer) {
+        let entry = self
+            .0
+            .entry(format!("day{}", day))
+            .or_default()
+            .entry(kind.key())
+            .or_default()
+            .entry(format!("part{}", part))
+            .or_default();
+        if entry.answer.is_none() {
+            entry.answer = known.answer;
+        }
+    }
+
+    pub fn get(&self, day: u32, kind: &InputKind, part: u8) -> Option<&KnownAnswer> {
+        self.0
+            .get(&format!("day{}", day))?
+            .get(&kind.key())?
+            .get(&format!("part{}", part))
+    }
+
+    pub fn check(&self, day: u32, kind: &InputKind, part: u8, answer: &str) -> Verdict {
+        match self.get(day, kind, part) {
+            Some(known) => known.check(answer),
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ANSWERS: &str = r#"
+        [day5.example.part1]
+        answer = "CMZ"
+
+        [day19.real.part1]
+        too_low = [851, 1032, 1121]
+        wrong = [1114, 1117]
+
+        [day22.real.part1]
+        too_high = [106158]
+    "#;
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        let example = InputKind::Example(0);
+        assert_eq!(answers.check(5, &example, 1, "CMZ"), Verdict::Correct);
+        assert_eq!(
+            answers.check(5, &example, 1, "MCD"),
+            Verdict::Incorrect {
+                expected: String::from("CMZ")
+            }
+        );
+        assert_eq!(answers.check(5, &example, 2, "MCD"), Verdict::Unknown);
+
+        let real = InputKind::Real;
+        assert_eq!(answers.check(19, &real, 1, "1000"), Verdict::KnownTooLow);
+        assert_eq!(answers.check(19, &real, 1, "1117"), Verdict::KnownWrong);
+        assert_eq!(answers.check(19, &real, 1, "1200"), Verdict::Unknown);
+        assert_eq!(answers.check(22, &real, 1, "200000"), Verdict::KnownTooHigh);
+    }
+
+    #[test]
+    fn test_answers_file() {
+        let answers = Answers::load_default().unwrap();
+        // From the sidecar beside the example
+        let known = answers.get(1, &InputKind::Example(0), 1).unwrap();
+        assert_eq!(known.check("24000"), Verdict::Correct);
+    }
+
+    #[test]
+    fn test_add() {
+        let mut answers =
+            Answers::parse("[day24.example.part1]\n[day24.example.part2]\nanswer = 7\n").unwrap();
+        let example = InputKind::Example(0);
+        for part in [1, 2] {
+            let known = KnownAnswer {
+                answer: Some(toml::Value::Integer(5)),
+                ..KnownAnswer::default()
+            };
+            answers.add(24, &example, part, known);
+        }
+        // An empty stub doesn't hide the sidecar, but a real answer does
+        assert_eq!(answers.check(24, &example, 1, "5"), Verdict::Correct);
+        assert_eq!(answers.check(24, &example, 2, "7"), Verdict::Correct);
+    }
+}
@@ -0,0 +1,311 @@
+use crate::parse::ParseError;
+use crate::runner::run_day;
+use crate::solution::Day;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Directory, relative to the crate root, holding benchmark results. These
+/// are specific to the machine that produced them so they are not checked in.
+pub const BENCH_DIR: &str = "bench";
+pub const HISTORY_FILE: &str = "history.json";
+pub const BASELINE_FILE: &str = "baseline.json";
+
+/// Phases faster than this in the baseline are never flagged, as timer noise
+/// swamps any real change.
+pub const NOISE_FLOOR_NS: u64 = 10_000;
+
+/// Median and 95th percentile of one phase, in nanoseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stats {
+    pub median: u64,
+    pub p95: u64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut nanos: Vec<u64> = samples.iter().map(|d| d.as_nanos() as u64).collect();
+        nanos.sort_unstable();
+        let n = nanos.len();
+        let median = if n % 2 == 1 {
+            nanos[n / 2]
+        } else {
+            (nanos[n / 2 - 1] + nanos[n / 2]) / 2
+        };
+        // Nearest rank
+        let p95 = nanos[(n * 95).div_ceil(100) - 1];
+        Some(Self { median, p95 })
+    }
+}
+
+/// Timings for one day, keyed by phase: `parse`, `part1` and `part2`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DayBench {
+    pub day: u32,
+    pub phases: BTreeMap<String, Stats>,
+}
+
+/// One invocation of the benchmark, as stored in the history file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BenchRecord {
+    /// Seconds since the Unix epoch
+    pub timestamp: u64,
+    pub iterations: u32,
+    pub days: Vec<DayBench>,
+}
+
+impl BenchRecord {
+    pub fn new(iterations: u32, days: Vec<DayBench>) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        Self {
+            timestamp,
+            iterations,
+            days,
+        }
+    }
+
+    pub fn get(&self, day: u32) -> Option<&DayBench> {
+        self.days.iter().find(|d| d.day == day)
+    }
+
+    /// Take every day in `other`, replacing any timings we already had for it.
+    pub fn update(&mut self, other: &BenchRecord) {
+        self.timestamp = other.timestamp;
+        self.iterations = other.iterations;
+        self.days.retain(|d| other.get(d.day).is_none());
+        self.days.extend(other.days.iter().cloned());
+        self.days.sort_by_key(|d| d.day);
+    }
+}
+
+/// Parse and solve `input` `iterations` times, timing each phase separately.
+pub fn bench_day(day: &Day, input: &str, iterations: u32) -> Result<DayBench, ParseError> {
+    let mut samples: BTreeMap<String, Vec<Duration>> = BTreeMap::new();
+    for _ in 0..iterations {
+        let run = run_day(day, input, &[1, 2])?;
+        samples
+            .entry(String::from("parse"))
+            .or_default()
+            .push(run.parse_time);
+        for part in run.parts {
+            samples
+                .entry(format!("part{}", part.part))
+                .or_default()
+                .push(part.elapsed);
+        }
+    }
+
+    let phases = samples
+        .into_iter()
+        .filter_map(|(phase, times)| Some((phase, Stats::from_samples(&times)?)))
+        .collect();
+    Ok(DayBench {
+        day: day.day,
+        phases,
+    })
+}
+
+/// A phase whose median got slower than the threshold allows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Regression {
+    pub day: u32,
+    pub phase: String,
+    pub baseline: u64,
+    pub current: u64,
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day {} {}: median {}ns -> {}ns ({:+.1}%)",
+            self.day,
+            self.phase,
+            self.baseline,
+            self.current,
+            (self.current as f64 / self.baseline as f64 - 1.0) * 100.0
+        )
+    }
+}
+
+/// Every phase in `current` whose median is more than `threshold` percent
+/// slower than in `baseline`. Days or phases missing from the baseline are
+/// not compared.
+pub fn regressions(
+    baseline: &BenchRecord,
+    current: &BenchRecord,
+    threshold: f64,
+) -> Vec<Regression> {
+    let mut found = vec![];
+    for day in &current.days {
+        let Some(base) = baseline.get(day.day) else {
+            continue;
+        };
+        for (phase, stats) in &day.phases {
+            let Some(base_stats) = base.phases.get(phase) else {
+                continue;
+            };
+            if base_stats.median < NOISE_FLOOR_NS {
+                continue;
+            }
+            let limit = base_stats.median as f64 * (1.0 + threshold / 100.0);
+            if stats.median as f64 > limit {
+                found.push(Regression {
+                    day: day.day,
+                    phase: phase.clone(),
+                    baseline: base_stats.median,
+                    current: stats.median,
+                });
+            }
+        }
+    }
+    found
+}
+
+#[derive(Debug)]
+pub enum BenchError {
+    Io(PathBuf, io::Error),
+    Json(PathBuf, serde_json::Error),
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BenchError::Io(path, e) => write!(f, "could not access {}: {}", path.display(), e),
+            BenchError::Json(path, e) => write!(f, "bad benchmark file {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for BenchError {}
+
+/// `bench/<file>`, where benchmark results are kept.
+pub fn bench_path(file: &str) -> PathBuf {
+    Path::new(BENCH_DIR).join(file)
+}
+
+fn read_json<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Option<T>, BenchError> {
+    match read_to_string(path) {
+        Ok(text) => serde_json::from_str(&text)
+            .map(Some)
+            .map_err(|e| BenchError::Json(path.to_path_buf(), e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(BenchError::Io(path.to_path_buf(), e)),
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), BenchError> {
+    if let Some(dir) = path.parent() {
+        create_dir_all(dir).map_err(|e| BenchError::Io(dir.to_path_buf(), e))?;
+    }
+    let text =
+        serde_json::to_string_pretty(value).map_err(|e| BenchError::Json(path.to_path_buf(), e))?;
+    write(path, text + "\n").map_err(|e| BenchError::Io(path.to_path_buf(), e))
+}
+
+/// Add `record` to the end of the history file at `path`, creating it if needed.
+pub fn append_history(path: &Path, record: &BenchRecord) -> Result<(), BenchError> {
+    let mut history: Vec<BenchRecord> = read_json(path)?.unwrap_or_default();
+    history.push(record.clone());
+    write_json(path, &history)
+}
+
+/// The saved baseline, if there is one.
+pub fn load_baseline(path: &Path) -> Result<Option<BenchRecord>, BenchError> {
+    read_json(path)
+}
+
+/// Merge `record` into the baseline at `path`, so benchmarking one day does
+/// not throw away the baseline for the rest.
+pub fn save_baseline(path: &Path, record: &BenchRecord) -> Result<(), BenchError> {
+    let baseline = match load_baseline(path)? {
+        Some(mut baseline) => {
+            baseline.update(record);
+            baseline
+        }
+        None => record.clone(),
+    };
+    write_json(path, &baseline)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn record(day: u32, phase: &str, median: u64) -> BenchRecord {
+        let phases = BTreeMap::from([(
+            phase.to_string(),
+            Stats {
+                median,
+                p95: median,
+            },
+        )]);
+        BenchRecord {
+            timestamp: 0,
+            iterations: 1,
+            days: vec![DayBench { day, phases }],
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<_> = (1..=20).map(Duration::from_nanos).collect();
+        let stats = Stats::from_samples(&samples).unwrap();
+        assert_eq!(
+            stats,
+            Stats {
+                median: 10,
+                p95: 19
+            }
+        );
+
+        let stats = Stats::from_samples(&[Duration::from_nanos(7)]).unwrap();
+        assert_eq!(stats, Stats { median: 7, p95: 7 });
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_regressions() {
+        let baseline = record(17, "part1", 100_000);
+        assert!(regressions(&baseline, &record(17, "part1", 105_000), 10.0).is_empty());
+        let found = regressions(&baseline, &record(17, "part1", 120_000), 10.0);
+        assert_eq!(found.len(), 1);
+        assert_eq!(
+            found[0].to_string(),
+            "day 17 part1: median 100000ns -> 120000ns (+20.0%)"
+        );
+
+        // Below the noise floor, or not in the baseline at all
+        assert!(regressions(&record(17, "parse", 100), &record(17, "parse", 900), 10.0).is_empty());
+        assert!(regressions(&baseline, &record(18, "part1", 900_000), 10.0).is_empty());
+    }
+
+    #[test]
+    fn test_update() {
+        let mut baseline = record(18, "part1", 5);
+        baseline.update(&record(17, "part1", 1));
+        baseline.update(&record(18, "part1", 2));
+        let days: Vec<_> = baseline
+            .days
+            .iter()
+            .map(|d| (d.day, d.phases["part1"].median))
+            .collect();
+        assert_eq!(days, vec![(17, 1), (18, 2)]);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let record = record(17, "part2", 1234);
+        let text = serde_json::to_string(&record).unwrap();
+        assert_eq!(serde_json::from_str::<BenchRecord>(&text).unwrap(), record);
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::hash_map::{DefaultHasher, Entry};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Where a sequence of states starts repeating: from step `start` on, each
+/// state is the same as the one `period` steps later.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as step `n`.
+    pub fn reduce(&self, n: u64) -> usize {
+        let start = self.start as u64;
+        if n < start {
+            n as usize
+        } else {
+            (start + (n - start) % self.period as u64) as usize
+        }
+    }
+
+    /// Carry a value tracked along the sequence, such as a height or score,
+    /// forward to step `n`. `history[i]` is the value after `i` steps, and
+    /// has to cover at least one whole period past the start. The value must
+    /// change by the same amount every time round, which holds for anything
+    /// added up a step at a time from the state.
+    pub fn extrapolate(&self, history: &[i64], n: u64) -> i64 {
+        assert!(
+            history.len() > self.start + self.period,
+            "history stops before the end of the first period"
+        );
+        if n < self.start as u64 {
+            return history[n as usize];
+        }
+        let periods = (n - self.start as u64) / self.period as u64;
+        let gain = history[self.start + self.period] - history[self.start];
+        history[self.reduce(n)] + periods as i64 * gain
+    }
+}
+
+/// Floyd's tortoise and hare, from `init` onwards. States are compared by
+/// `key`, which has to capture everything that decides the next state.
+/// Never returns if the states never repeat.
+pub fn floyd<S: Clone, K: Eq>(
+    init: &S,
+    mut step: impl FnMut(&S) -> S,
+    key: impl Fn(&S) -> K,
+) -> Cycle {
+    // Meet somewhere inside the cycle, with the hare going twice as fast
+    let mut tortoise = step(init);
+    let mut hare = step(&tortoise);
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        let halfway = step(&hare);
+        hare = step(&halfway);
+    }
+
+    // The hare is now a whole number of periods ahead, so walking one from
+    // the beginning at the same pace meets it where the cycle starts
+    let mut start = 0;
+    tortoise = init.clone();
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    // Then go round once to measure it
+    let mut period = 1;
+    let target = key(&tortoise);
+    hare = step(&tortoise);
+    while key(&hare) != target {
+        hare = step(&hare);
+        period += 1;
+    }
+    Cycle { start, period }
+}
+
+/// Brent's algorithm, with the same contract as `floyd`. It takes fewer
+/// steps, which matters when stepping is the expensive part.
+pub fn brent<S: Clone, K: Eq>(
+    init: &S,
+    mut step: impl FnMut(&S) -> S,
+    key: impl Fn(&S) -> K,
+) -> Cycle {
+    // Find the period, bringing the tortoise up to the hare each time the
+    // hare has gone a power of two further
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = key(init);
+    let mut hare = step(init);
+    loop {
+        let hare_key = key(&hare);
+        if hare_key == tortoise {
+            break;
+        }
+        if power == period {
+            tortoise = hare_key;
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // Start the hare a period ahead, so they meet where the cycle starts
+    let mut start = 0;
+    let mut tortoise = init.clone();
+    let mut hare = init.clone();
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    while key(&tortoise) != key(&hare) {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, period }
+}
+
+/// Finds a cycle from one key per step, for simulations that update in
+/// place rather than being stepped from a copy. Every key is kept, so large
+/// states are best reduced with `fingerprint`.
+#[derive(Clone, Debug)]
+pub struct Detector<K> {
+    seen: HashMap<K, usize>,
+    steps: usize,
+}
+
+impl<K: Hash + Eq> Default for Detector<K> {
+    fn default() -> Self {
+        Self {
+            seen: HashMap::new(),
+            steps: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq> Detector<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the key of the next state, and return the cycle if it has
+    /// been seen before.
+    pub fn observe(&mut self, key: K) -> Option<Cycle> {
+        let step = self.steps;
+        self.steps += 1;
+        match self.seen.entry(key) {
+            Entry::Occupied(first) => Some(Cycle {
+                start: *first.get(),
+                period: step - first.get(),
+            }),
+            Entry::Vacant(entry) => {
+                entry.insert(step);
+                None
+            }
+        }
+    }
+}
+
+/// A hash of `value`, for a key much smaller than the state it stands for.
+/// Two states could in principle share one, but with 64 bits it would take
+/// billions of steps to be likely.
+pub fn fingerprint<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Counts up to 5, then goes round 5, 6, 7 forever.
+    fn step(x: &u32) -> u32 {
+        if *x < 7 {
+            x + 1
+        } else {
+            5
+        }
+    }
+
+    #[test]
+    fn test_detect() {
+        let expected = Cycle {
+            start: 5,
+            period: 3,
+        };
+        assert_eq!(floyd(&0, step, |&x| x), expected);
+        assert_eq!(brent(&0, step, |&x| x), expected);
+        // Starting inside the cycle
+        assert_eq!(brent(&6, step, |&x| x).start, 0);
+        assert_eq!(floyd(&6, step, |&x| x).period, 3);
+
+        let mut detector = Detector::new();
+        let mut x = 0;
+        let cycle = loop {
+            if let Some(cycle) = detector.observe(fingerprint(&x)) {
+                break cycle;
+            }
+            x = step(&x);
+        };
+        assert_eq!(cycle, expected);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        let cycle = Cycle {
+            start: 5,
+            period: 3,
+        };
+        assert_eq!(cycle.reduce(4), 4);
+        assert_eq!(cycle.reduce(9), 6);
+        // Running total of the states
+        let history: Vec<i64> = std::iter::successors(Some(0), |x| Some(step(x)))
+            .scan(0, |total, x| {
+                let before = *total;
+                *total += x as i64;
+                Some(before)
+            })
+            .take(20)
+            .collect();
+        for n in 0..20 {
+            assert_eq!(cycle.extrapolate(&history[..9], n), history[n as usize]);
+        }
+        assert_eq!(cycle.extrapolate(&history, 1_000_000), 5_999_979);
+    }
+}
@@ -1,47 +1,735 @@
-#[aoc_generator(day1)]
-pub fn load_input(input: &str) -> Vec<u64> {
-    let mut sums: Vec<u64> = vec![];
-    let mut thissum = 0;
-    for line in input.lines() {
-        //println!("{}", line);
-        if line.is_empty() {
-            sums.push(thissum);
-            thissum = 0;
+use crate::optimize::{maximize, Problem};
+use crate::parse::{Line, Lines, ParseError};
+use crate::solution::Solution;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::io::{self, BufRead};
+
+/// One elf's snacks, in the order they were listed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Elf {
+    /// Position in the input, counting from 0.
+    pub index: usize,
+    pub items: Vec<u64>,
+}
+
+impl Elf {
+    pub fn total(&self) -> u64 {
+        self.items.iter().sum()
+    }
+}
+
+/// Every elf in the input. Elves are separated by blank lines; runs of
+/// blank lines and the end of the input both close the current elf, so a
+/// missing or doubled trailing blank line makes no difference.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ElfInventory {
+    pub elves: Vec<Elf>,
+}
+
+impl ElfInventory {
+    /// (index, total) for every elf, in input order.
+    pub fn totals(&self) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.elves.iter().map(|elf| (elf.index, elf.total()))
+    }
+
+    /// The `k` elves carrying the most calories as (index, total), most
+    /// first. Ties go to the elf listed first.
+    pub fn top_k(&self, k: usize) -> Vec<(usize, u64)> {
+        top_k(self.totals(), k)
+    }
+}
+
+/// The largest totals seen so far, keeping only `k` at a time.
+struct TopK {
+    k: usize,
+    // Min heap on (total, later index), so the top is the first to go
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+}
+
+impl TopK {
+    fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    fn push(&mut self, index: usize, total: u64) {
+        if self.k == 0 {
+            return;
+        }
+        self.heap.push(Reverse((total, Reverse(index))));
+        if self.heap.len() > self.k {
+            self.heap.pop();
+        }
+    }
+
+    /// (index, total), most first.
+    fn into_vec(self) -> Vec<(usize, u64)> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((total, Reverse(index)))| (index, total))
+            .collect()
+    }
+}
+
+fn top_k(totals: impl Iterator<Item = (usize, u64)>, k: usize) -> Vec<(usize, u64)> {
+    let mut top = TopK::new(k);
+    for (index, total) in totals {
+        top.push(index, total);
+    }
+    top.into_vec()
+}
+
+/// The calorie count on `line`, or None if it's blank and so ends an elf.
+/// Stray carriage returns from CRLF files are ignored.
+fn item(line: &Line) -> Result<Option<u64>, ParseError> {
+    let text = line.text.trim_end_matches('\r');
+    if text.is_empty() {
+        return Ok(None);
+    }
+    line.parse(text, "a calorie count").map(Some)
+}
+
+/// What's kept of each elf while splitting: all the items, or just the sum.
+trait Group: Default {
+    fn add(&mut self, calories: u64);
+    fn is_empty(&self) -> bool;
+}
+
+impl Group for Vec<u64> {
+    fn add(&mut self, calories: u64) {
+        self.push(calories);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+/// Just the total, and whether any items were added to it.
+#[derive(Default)]
+struct Total(u64, bool);
+
+impl Group for Total {
+    fn add(&mut self, calories: u64) {
+        self.0 += calories;
+        self.1 = true;
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.1
+    }
+}
+
+/// Splits lines into elves the same way wherever they come from.
+#[derive(Default)]
+struct Splitter<G> {
+    index: usize,
+    group: G,
+}
+
+impl<G: Group> Splitter<G> {
+    /// Take in one line, returning the elf it closes if any.
+    fn feed(&mut self, line: &Line) -> Result<Option<(usize, G)>, ParseError> {
+        match item(line)? {
+            Some(calories) => {
+                self.group.add(calories);
+                Ok(None)
+            }
+            None => Ok(self.close()),
+        }
+    }
+
+    /// The elf still open at the end of the input, if any.
+    fn finish(mut self) -> Option<(usize, G)> {
+        self.close()
+    }
+
+    fn close(&mut self) -> Option<(usize, G)> {
+        if self.group.is_empty() {
+            return None;
+        }
+        self.index += 1;
+        Some((self.index - 1, std::mem::take(&mut self.group)))
+    }
+}
+
+pub fn load_input(input: &str) -> Result<ElfInventory, ParseError> {
+    let mut elves = vec![];
+    let mut splitter = Splitter::default();
+    for line in Lines::new(1, input) {
+        if let Some((index, items)) = splitter.feed(&line)? {
+            elves.push(Elf { index, items });
+        }
+    }
+    if let Some((index, items)) = splitter.finish() {
+        elves.push(Elf { index, items });
+    }
+    Ok(ElfInventory { elves })
+}
+
+/// What `summarize` finds in one pass over a stream of elves.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    pub count: usize,
+    pub mean: f64,
+    /// Exact for up to five elves, and an estimate beyond that.
+    pub median: f64,
+    /// (index, total) of the heaviest elf.
+    pub max: Option<(usize, u64)>,
+    /// (index, total) of the `k` heaviest elves, most first.
+    pub top: Vec<(usize, u64)>,
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StreamError::Io(e) => write!(f, "could not read elves: {}", e),
+            StreamError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+/// Read elves from `reader` one line at a time, keeping the `k` heaviest
+/// and running statistics but never more than one line of the input.
+/// Elves are split exactly as `load_input` splits them.
+pub fn summarize(mut reader: impl BufRead, k: usize) -> Result<Summary, StreamError> {
+    let mut splitter = Splitter::<Total>::default();
+    let mut top = TopK::new(k);
+    let mut max: Option<(usize, u64)> = None;
+    let mut median = Median::default();
+    let mut sum = 0u128;
+
+    let mut add = |(index, Total(total, _)): (usize, Total)| {
+        top.push(index, total);
+        if max.is_none_or(|(_, most)| total > most) {
+            max = Some((index, total));
+        }
+        median.add(total as f64);
+        sum += total as u128;
+    };
+
+    let mut text = String::new();
+    let mut number = 0;
+    loop {
+        text.clear();
+        if reader.read_line(&mut text).map_err(StreamError::Io)? == 0 {
+            break;
+        }
+        number += 1;
+        let line = Line {
+            day: 1,
+            number,
+            text: text.strip_suffix('\n').unwrap_or(&text),
+        };
+        if let Some(elf) = splitter.feed(&line).map_err(StreamError::Parse)? {
+            add(elf);
+        }
+    }
+    if let Some(elf) = splitter.finish() {
+        add(elf);
+    }
+
+    let count = median.count;
+    Ok(Summary {
+        count,
+        mean: if count == 0 {
+            0.0
+        } else {
+            sum as f64 / count as f64
+        },
+        median: median.estimate(),
+        max,
+        top: top.into_vec(),
+    })
+}
+
+/// Running median in constant space, using the P² algorithm of Jain and
+/// Chlamtac: five markers track the minimum, quartiles, median and maximum,
+/// nudged towards where they should be as values arrive.
+#[derive(Clone, Debug, Default)]
+struct Median {
+    count: usize,
+    // Marker heights, and their actual and desired positions
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+}
+
+impl Median {
+    const STEP: [f64; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+    fn add(&mut self, x: f64) {
+        if self.count < 5 {
+            self.heights[self.count] = x;
+            self.count += 1;
+            if self.count == 5 {
+                self.heights.sort_by(f64::total_cmp);
+                self.positions = [0.0, 1.0, 2.0, 3.0, 4.0];
+                self.desired = [0.0, 1.0, 2.0, 3.0, 4.0];
+            }
+            return;
+        }
+        self.count += 1;
+
+        let q = &mut self.heights;
+        let cell = if x < q[0] {
+            q[0] = x;
+            0
+        } else if x >= q[4] {
+            q[4] = x;
+            3
         } else {
-            thissum += line.parse::<u64>().unwrap();
+            (0..4).rfind(|&i| q[i] <= x).unwrap()
+        };
+        for n in &mut self.positions[cell + 1..] {
+            *n += 1.0;
+        }
+        for (n, step) in self.desired.iter_mut().zip(Self::STEP) {
+            *n += step;
+        }
+
+        for i in 1..4 {
+            let n = &self.positions;
+            let off = self.desired[i] - n[i];
+            if (off >= 1.0 && n[i + 1] - n[i] > 1.0) || (off <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = off.signum();
+                let q = &self.heights;
+                let parabolic = q[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+                self.heights[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    let j = (i as f64 + d) as usize;
+                    q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                self.positions[i] += d;
+            }
         }
     }
-    sums
+
+    fn estimate(&self) -> f64 {
+        match self.count {
+            0 => 0.0,
+            1..=4 => {
+                let mut seen = self.heights[..self.count].to_vec();
+                seen.sort_by(f64::total_cmp);
+                let mid = seen.len() / 2;
+                if seen.len().is_multiple_of(2) {
+                    (seen[mid - 1] + seen[mid]) / 2.0
+                } else {
+                    seen[mid]
+                }
+            }
+            _ => self.heights[2],
+        }
+    }
+}
+
+/// One item, remembering which elf it came from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Snack {
+    pub elf: usize,
+    pub calories: u64,
 }
 
-#[aoc(day01, part1)]
-pub fn part1(input: &[u64]) -> u64 {
-    *input.iter().max().unwrap()
+/// How to split the snacks between carriers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Branch and bound to a provably smallest largest load. Only practical
+    /// for a few dozen snacks.
+    Exact,
+    /// The better of LPT and Karmarkar-Karp, which is fast at any size but
+    /// may be a little off the best.
+    Heuristic,
 }
 
-#[aoc(day01, part2)]
-pub fn part2(input: &[u64]) -> u64 {
-    let mut temp: Vec<_> = input.to_vec();
-    temp.sort();
-    temp.iter().rev().take(3).sum::<u64>()
+/// Every snack handed to one of the carriers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Loads {
+    pub carriers: Vec<Vec<Snack>>,
+}
+
+impl Loads {
+    pub fn totals(&self) -> Vec<u64> {
+        self.carriers
+            .iter()
+            .map(|snacks| snacks.iter().map(|s| s.calories).sum())
+            .collect()
+    }
+
+    /// The heaviest load, which the split tries to keep as small as it can.
+    pub fn largest(&self) -> u64 {
+        self.totals().into_iter().max().unwrap_or(0)
+    }
+}
+
+impl ElfInventory {
+    /// Every snack in the input, heaviest first.
+    pub fn snacks(&self) -> Vec<Snack> {
+        let mut snacks: Vec<Snack> = self
+            .elves
+            .iter()
+            .flat_map(|elf| {
+                elf.items.iter().map(|&calories| Snack {
+                    elf: elf.index,
+                    calories,
+                })
+            })
+            .collect();
+        snacks.sort_by_key(|s| Reverse(s.calories));
+        snacks
+    }
+
+    /// Split every snack between `carriers` so the heaviest load is as light
+    /// as possible. Panics if there are no carriers.
+    pub fn balance(&self, carriers: usize, mode: Mode) -> Loads {
+        assert!(carriers > 0, "no one to carry the snacks");
+        let snacks = self.snacks();
+        let assigned = match mode {
+            Mode::Exact => {
+                let problem = Balance {
+                    calories: snacks.iter().map(|s| s.calories).collect(),
+                    carriers,
+                };
+                let best = maximize(&problem, problem.start());
+                problem.complete(&best.state)
+            }
+            Mode::Heuristic => {
+                let calories: Vec<u64> = snacks.iter().map(|s| s.calories).collect();
+                let lpt = lpt(&calories, &vec![0; carriers], &[]);
+                let kk = karmarkar_karp(&calories, carriers);
+                if largest(&calories, &kk, carriers) < largest(&calories, &lpt, carriers) {
+                    kk
+                } else {
+                    lpt
+                }
+            }
+        };
+        let mut loads = vec![vec![]; carriers];
+        for (snack, carrier) in snacks.into_iter().zip(assigned) {
+            loads[carrier].push(snack);
+        }
+        Loads { carriers: loads }
+    }
+}
+
+/// The heaviest load when each of `calories` goes to the carrier at the same
+/// place in `assigned`.
+fn largest(calories: &[u64], assigned: &[usize], carriers: usize) -> u64 {
+    let mut loads = vec![0; carriers];
+    for (&c, &carrier) in calories.iter().zip(assigned) {
+        loads[carrier] += c;
+    }
+    loads.into_iter().max().unwrap_or(0)
+}
+
+/// Longest processing time first: each of `calories`, heaviest first, goes
+/// to whoever carries least so far. Carriers start at `loads`, with the
+/// first snacks already given out as in `assigned`.
+fn lpt(calories: &[u64], loads: &[u64], assigned: &[usize]) -> Vec<usize> {
+    let mut lightest: BinaryHeap<_> = loads
+        .iter()
+        .enumerate()
+        .map(|(carrier, &load)| Reverse((load, carrier)))
+        .collect();
+    let mut assigned = assigned.to_vec();
+    for &c in &calories[assigned.len()..] {
+        let Reverse((load, carrier)) = lightest.pop().unwrap();
+        assigned.push(carrier);
+        lightest.push(Reverse((load + c, carrier)));
+    }
+    assigned
+}
+
+/// Karmarkar and Karp's differencing for any number of carriers. Each snack
+/// starts as a partial split with one non-empty part, and the two splits
+/// with the widest spread are repeatedly merged by pairing the heaviest
+/// parts of one with the lightest of the other.
+fn karmarkar_karp(calories: &[u64], carriers: usize) -> Vec<usize> {
+    // (spread, sums heaviest first, snacks in each part)
+    type Split = (u64, Vec<u64>, Vec<Vec<usize>>);
+    let mut splits: BinaryHeap<Split> = calories
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let mut sums = vec![0; carriers];
+            let mut parts = vec![vec![]; carriers];
+            sums[0] = c;
+            parts[0].push(i);
+            (c, sums, parts)
+        })
+        .collect();
+
+    while splits.len() > 1 {
+        let (_, a_sums, a_parts) = splits.pop().unwrap();
+        let (_, b_sums, b_parts) = splits.pop().unwrap();
+        let mut merged: Vec<(u64, Vec<usize>)> = a_sums
+            .into_iter()
+            .zip(a_parts)
+            .zip(b_sums.into_iter().zip(b_parts).rev())
+            .map(|((a, mut a_part), (b, b_part))| {
+                a_part.extend(b_part);
+                (a + b, a_part)
+            })
+            .collect();
+        merged.sort_by_key(|(sum, _)| Reverse(*sum));
+        let (sums, parts): (Vec<u64>, Vec<Vec<usize>>) = merged.into_iter().unzip();
+        splits.push((sums[0] - sums[carriers - 1], sums, parts));
+    }
+
+    let mut assigned = vec![0; calories.len()];
+    if let Some((_, _, parts)) = splits.pop() {
+        for (carrier, part) in parts.into_iter().enumerate() {
+            for i in part {
+                assigned[i] = carrier;
+            }
+        }
+    }
+    assigned
+}
+
+/// Snacks handed out heaviest first, with a largest load to minimise.
+struct Balance {
+    /// Heaviest first.
+    calories: Vec<u64>,
+    carriers: usize,
+}
+
+/// The carrier given each snack so far, and what each carries.
+#[derive(Clone, Debug)]
+struct Handout {
+    assigned: Vec<usize>,
+    loads: Vec<u64>,
+}
+
+impl Balance {
+    fn start(&self) -> Handout {
+        Handout {
+            assigned: vec![],
+            loads: vec![0; self.carriers],
+        }
+    }
+
+    /// The rest of the snacks given out by LPT.
+    fn complete(&self, state: &Handout) -> Vec<usize> {
+        lpt(&self.calories, &state.loads, &state.assigned)
+    }
+}
+
+impl Problem for Balance {
+    type State = Handout;
+    type Key = (usize, Vec<u64>);
+
+    fn branch(&self, state: &Handout) -> Vec<Handout> {
+        let Some(&c) = self.calories.get(state.assigned.len()) else {
+            return vec![];
+        };
+        let mut next = vec![];
+        let mut tried = vec![];
+        for (carrier, &load) in state.loads.iter().enumerate() {
+            // Carriers with the same load are interchangeable
+            if tried.contains(&load) {
+                continue;
+            }
+            tried.push(load);
+            let mut handout = state.clone();
+            handout.assigned.push(carrier);
+            handout.loads[carrier] += c;
+            next.push(handout);
+        }
+        next
+    }
+
+    /// Every handout can be finished off by LPT, so it's worth what that
+    /// finish would be, negated as the search maximises.
+    fn value(&self, state: &Handout) -> i64 {
+        -(largest(&self.calories, &self.complete(state), self.carriers) as i64)
+    }
+
+    /// The heaviest load can't get lighter, or be below an even share or
+    /// the heaviest snack.
+    fn bound(&self, state: &Handout) -> i64 {
+        let total: u64 = self.calories.iter().sum();
+        let even = total.div_ceil(self.carriers as u64);
+        let heaviest = state.loads.iter().copied().max().unwrap_or(0);
+        let snack = self.calories.first().copied().unwrap_or(0);
+        -(heaviest.max(even).max(snack) as i64)
+    }
+
+    /// Only how much each carries matters from here, not who carries it.
+    fn key(&self, state: &Handout) -> (usize, Vec<u64>) {
+        let mut loads = state.loads.clone();
+        loads.sort_unstable();
+        (state.assigned.len(), loads)
+    }
+}
+
+pub fn part1(input: &ElfInventory) -> u64 {
+    input.top_k(1).iter().map(|&(_, total)| total).sum()
+}
+
+pub fn part2(input: &ElfInventory) -> u64 {
+    input.top_k(3).iter().map(|&(_, total)| total).sum()
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = ElfInventory;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/01a.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(1, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 24000);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/01a.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(1, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 45000);
     }
+
+    #[test]
+    fn test_top_k() {
+        let input = load(1, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
+        assert_eq!(input.elves.len(), 5);
+        assert_eq!(input.elves[3].items, vec![7000, 8000, 9000]);
+        assert_eq!(input.top_k(3), vec![(3, 24000), (2, 11000), (4, 10000)]);
+        assert_eq!(input.top_k(0), vec![]);
+        assert_eq!(input.top_k(9).len(), 5);
+
+        let tied = load_input("5\n\n3\n2\n\n1").unwrap();
+        assert_eq!(tied.top_k(2), vec![(0, 5), (1, 5)]);
+    }
+
+    #[test]
+    fn test_load_input() {
+        // No trailing blank line, CRLF, and extra blank lines
+        let input = load_input("1\r\n2\r\n\r\n\r\n3\r\n\r\n4").unwrap();
+        let totals: Vec<_> = input.totals().collect();
+        assert_eq!(totals, vec![(0, 3), (1, 3), (2, 4)]);
+        assert_eq!(load_input("\n1\n\n").unwrap().elves.len(), 1);
+        assert_eq!(load_input("").unwrap(), ElfInventory::default());
+
+        let err = load_input("1\n2x\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+
+    #[test]
+    fn test_summarize() {
+        let input = load(1, &InputKind::Example(0)).unwrap();
+        let summary = summarize(input.as_bytes(), 3).unwrap();
+        assert_eq!(summary.count, 5);
+        assert_eq!(summary.mean, 11000.0);
+        assert_eq!(summary.median, 10000.0);
+        assert_eq!(summary.max, Some((3, 24000)));
+        assert_eq!(summary.top, load_input(&input).unwrap().top_k(3));
+
+        // Split the same way as load_input
+        let text = "1\r\n2\r\n\r\n\r\n3\r\n\r\n4";
+        let summary = summarize(text.as_bytes(), 9).unwrap();
+        let totals: Vec<_> = load_input(text).unwrap().totals().collect();
+        assert_eq!(summary.count, totals.len());
+        assert_eq!(summary.median, 3.0);
+        assert_eq!(summarize(&b""[..], 1).unwrap(), Summary::default());
+
+        let err = summarize(&b"1\n\n2x\n"[..], 1).unwrap_err();
+        assert!(matches!(err, StreamError::Parse(e) if e.line == 3));
+    }
+
+    fn inventory(calories: &[u64]) -> ElfInventory {
+        let text: Vec<String> = calories.iter().map(|c| c.to_string()).collect();
+        load_input(&text.join("\n\n")).unwrap()
+    }
+
+    #[test]
+    fn test_balance() {
+        let input = load(1, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
+        assert_eq!(input.balance(2, Mode::Exact).largest(), 28000);
+        assert_eq!(input.balance(3, Mode::Exact).largest(), 19000);
+        for carriers in 1..5 {
+            let loads = input.balance(carriers, Mode::Heuristic);
+            assert_eq!(loads.totals().iter().sum::<u64>(), 55000);
+            assert!(loads.largest() >= input.balance(carriers, Mode::Exact).largest());
+        }
+
+        // Differencing leaves 16 and 14, LPT 17 and 13, but 15 and 15 works
+        let input = inventory(&[8, 7, 6, 5, 4]);
+        assert_eq!(input.balance(2, Mode::Heuristic).largest(), 16);
+        let loads = input.balance(2, Mode::Exact);
+        assert_eq!(loads.totals(), vec![15, 15]);
+        let mut elves: Vec<usize> = loads.carriers.concat().iter().map(|s| s.elf).collect();
+        elves.sort();
+        assert_eq!(elves, vec![0, 1, 2, 3, 4]);
+
+        // Against every way to split eight snacks three ways
+        let calories: Vec<u64> = (1..9).map(|i| i * i * 37 % 101).collect();
+        let mut best = u64::MAX;
+        for mut ways in 0..3usize.pow(8) {
+            let mut loads = [0; 3];
+            for c in &calories {
+                loads[ways % 3] += c;
+                ways /= 3;
+            }
+            best = best.min(*loads.iter().max().unwrap());
+        }
+        let input = inventory(&calories);
+        assert_eq!(input.balance(3, Mode::Exact).largest(), best);
+        assert_eq!(inventory(&[]).balance(2, Mode::Exact).largest(), 0);
+    }
+
+    #[test]
+    fn test_median() {
+        // Totals 1..=1001 shuffled, whose median is 501
+        let mut median = Median::default();
+        for i in 0..1001u64 {
+            median.add((i * 389 % 1001 + 1) as f64);
+        }
+        assert!((median.estimate() - 501.0).abs() < 10.0);
+
+        let mut median = Median::default();
+        for x in [4.0, 1.0, 3.0, 2.0] {
+            median.add(x);
+        }
+        assert_eq!(median.estimate(), 2.5);
+    }
 }
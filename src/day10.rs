@@ -1,3 +1,6 @@
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
+
 #[derive(Debug, Copy, Clone)]
 pub enum Command {
     Addx(i64),
@@ -6,25 +9,28 @@ pub enum Command {
 
 type Input = Command;
 
-#[aoc_generator(day10)]
-pub fn load_input(input: &str) -> Vec<Input> {
+pub fn load_input(input: &str) -> Result<Vec<Input>, ParseError> {
     let mut output = vec![];
-    for line in input.lines() {
-        let words: Vec<_> = line.split(' ').collect();
+    for line in Lines::new(10, input) {
+        let words: Vec<_> = line.text.split(' ').collect();
         match words[0] {
             "addx" => {
-                output.push(Command::Addx(words[1].parse::<i64>().unwrap()));
+                let arg = words
+                    .get(1)
+                    .copied()
+                    .unwrap_or(&line.text[line.text.len()..]);
+                output.push(Command::Addx(line.parse::<i64>(arg, "an integer")?));
             }
             "noop" => {
                 output.push(Command::Noop);
             }
-            _ => (),
+            "" => (),
+            _ => return Err(line.error_at(1, "`addx` or `noop`")),
         }
     }
-    output
+    Ok(output)
 }
 
-#[aoc(day10, part1)]
 pub fn part1(input: &[Input]) -> i64 {
     let mut x = 1;
     let mut i = 1;
@@ -64,8 +70,8 @@ fn draw(i: usize, x: i64, image: &mut [[char; 40]; 6]) {
     }
 }
 
-#[aoc(day10, part2)]
-pub fn part2(input: &[Input]) -> i64 {
+/// The image drawn on the CRT, one line per row.
+pub fn part2(input: &[Input]) -> String {
     let mut x: i64 = 1;
     let mut i = 1;
     let mut image = [['.'; 40]; 6];
@@ -84,33 +90,51 @@ pub fn part2(input: &[Input]) -> i64 {
         }
     }
 
-    for line in image {
-        for c in line {
-            print!("{}", c);
-        }
-        println!();
+    image
+        .iter()
+        .map(|line| line.iter().collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<Input>;
+    type Answer1 = i64;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
     }
-    println!();
-    0
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/10.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(10, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 13140);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/10.txt").unwrap();
-        let input = load_input(&input);
-        part2(&input);
-        assert_eq!(0, 0);
+        let input = load(10, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
+        let image = part2(&input);
+        assert_eq!(image.lines().count(), 6);
+        assert!(image.starts_with("##..##..##..##..##..##..##..##..##..##..\n###...###"));
     }
 }
@@ -1,3 +1,5 @@
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
 use scan_fmt::scan_fmt;
 
 type Input = Monkey;
@@ -9,7 +11,7 @@ enum OpArg {
 }
 
 #[derive(Debug)]
-struct Monkey {
+pub struct Monkey {
     items: Vec<usize>,
     op: char,
     oparg: OpArg,
@@ -18,42 +20,42 @@ struct Monkey {
     false_id: usize,
 }
 
-#[aoc_generator(day11)]
-fn load_input(input: &str) -> Vec<Input> {
+fn load_input(input: &str) -> Result<Vec<Input>, ParseError> {
     let mut output = vec![];
-    let mut lines = input.lines();
+    let mut lines = Lines::new(11, input);
     loop {
-        lines.next(); // Monkey #:
-        let temp: Vec<_> = lines.next().unwrap().split(": ").collect();
-        let items: Vec<_> = temp[1]
+        lines.next_or("`Monkey N:`")?;
+        let line = lines.next_or("`  Starting items: ...`")?;
+        let temp: Vec<_> = line.text.split(": ").collect();
+        let items_str = temp
+            .get(1)
+            .ok_or_else(|| line.scan_error("  Starting items: {}"))?;
+        let items = items_str
             .split(", ")
-            .map(|s| s.parse::<usize>().unwrap())
-            .collect();
-        let (op, num) = scan_fmt!(
-            lines.next().unwrap(),
-            "  Operation: new = old {} {}",
-            char,
-            String
-        )
-        .unwrap();
+            .map(|s| line.parse::<usize>(s, "a worry level"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let line = lines.next_or("`  Operation: ...`")?;
+        let (op, num) = scan_fmt!(line.text, "  Operation: new = old {} {}", char, String)
+            .map_err(|_| line.scan_error("  Operation: new = old {} {}"))?;
+        if op != '+' && op != '*' {
+            let column = line.text.find("old ").map_or(1, |i| i + 5);
+            return Err(line.error_at(column, "`+` or `*`"));
+        }
         let oparg = if num != "old" {
-            OpArg::Num(num.parse::<usize>().unwrap())
+            let num_str = line.text.trim_end().rsplit(' ').next().unwrap_or(line.text);
+            OpArg::Num(line.parse::<usize>(num_str, "a number or `old`")?)
         } else {
             OpArg::Old
         };
-        let divisor = scan_fmt!(lines.next().unwrap(), "  Test: divisible by {}", usize).unwrap();
-        let truth_id = scan_fmt!(
-            lines.next().unwrap(),
-            "    If true: throw to monkey {}",
-            usize
-        )
-        .unwrap();
-        let false_id = scan_fmt!(
-            lines.next().unwrap(),
-            "    If false: throw to monkey {}",
-            usize
-        )
-        .unwrap();
+        let line = lines.next_or("`  Test: ...`")?;
+        let divisor = scan_fmt!(line.text, "  Test: divisible by {}", usize)
+            .map_err(|_| line.scan_error("  Test: divisible by {}"))?;
+        let line = lines.next_or("`    If true: ...`")?;
+        let truth_id = scan_fmt!(line.text, "    If true: throw to monkey {}", usize)
+            .map_err(|_| line.scan_error("    If true: throw to monkey {}"))?;
+        let line = lines.next_or("`    If false: ...`")?;
+        let false_id = scan_fmt!(line.text, "    If false: throw to monkey {}", usize)
+            .map_err(|_| line.scan_error("    If false: throw to monkey {}"))?;
         output.push(Monkey {
             items: items.clone(),
             op,
@@ -66,7 +68,7 @@ fn load_input(input: &str) -> Vec<Input> {
             break;
         }
     }
-    output
+    Ok(output)
 }
 
 fn monkey_inspection(
@@ -107,7 +109,6 @@ fn monkey_inspection(
     output
 }
 
-#[aoc(day11, part1)]
 fn part1(input: &[Input]) -> usize {
     let mut monkey_arr: Vec<Vec<usize>> = vec![vec![]; input.len()];
     for (i, monkey) in input.iter().enumerate() {
@@ -130,7 +131,6 @@ fn part1(input: &[Input]) -> usize {
     monkeys[0] * monkeys[1]
 }
 
-#[aoc(day11, part2)]
 fn part2(input: &[Input]) -> usize {
     let mut monkey_arr: Vec<Vec<usize>> = vec![vec![]; input.len()];
     let mut modulo = 1;
@@ -154,22 +154,42 @@ fn part2(input: &[Input]) -> usize {
     monkeys[0] * monkeys[1]
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Vec<Input>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/11.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(11, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 10605);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/11.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(11, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 2713310158);
     }
 }
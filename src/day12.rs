@@ -1,4 +1,8 @@
-use std::collections::{HashMap, HashSet};
+use crate::geom::Point2;
+use crate::grid::Grid;
+use crate::parse::{Lines, ParseError};
+use crate::search::{multi_bfs, shortest_path, Connected};
+use crate::solution::Solution;
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 enum Tile {
@@ -7,33 +11,21 @@ enum Tile {
     Value(i32),
 }
 
-struct HeightMap {
-    map: Vec<Vec<Tile>>,
+pub struct HeightMap {
+    map: Grid<Tile>,
+    start: Point2<usize>,
+    end: Point2<usize>,
 }
 
 impl HeightMap {
-    fn start_pos(&self) -> (usize, usize) {
-        for (row, line) in self.map.iter().enumerate() {
-            for (col, tile) in line.iter().enumerate() {
-                if *tile == Tile::Start {
-                    return (col, row);
-                }
-            }
-        }
-        unreachable!()
+    fn start_pos(&self) -> Point2<usize> {
+        self.start
     }
-    fn end_pos(&self) -> (usize, usize) {
-        for (row, line) in self.map.iter().enumerate() {
-            for (col, tile) in line.iter().enumerate() {
-                if *tile == Tile::End {
-                    return (col, row);
-                }
-            }
-        }
-        unreachable!()
+    fn end_pos(&self) -> Point2<usize> {
+        self.end
     }
-    fn height(&self, pos: (usize, usize)) -> i32 {
-        match self.map[pos.1][pos.0] {
+    fn height(&self, pos: Point2<usize>) -> i32 {
+        match self.map[pos] {
             Tile::Start => 0,
             Tile::End => 25,
             Tile::Value(num) => num,
@@ -43,175 +35,126 @@ impl HeightMap {
 
 type Input = HeightMap;
 
-// let input_iter = input.iter();
-// loop {
-//     let line = input_iter.next().unwrap();
-// }
-//
-// let comma_separated = line.split(',').collect();
-
-#[aoc_generator(day12)]
-fn load_input(input: &str) -> Input {
-    let mut output = HeightMap { map: vec![] };
-    for line in input.lines() {
-        let mut temp = vec![];
-        for c in line.chars() {
-            match c {
-                'S' => {
-                    temp.push(Tile::Start);
-                }
-                'E' => {
-                    temp.push(Tile::End);
-                }
-                _ => {
-                    let cc = (c as i32) - 97;
-                    temp.push(Tile::Value(cc));
-                }
+/// Where the one `marker` on the map is.
+fn find_one(day: u32, input: &str, marker: char) -> Result<Point2<usize>, ParseError> {
+    let mut found: Option<(usize, Point2<usize>)> = None;
+    let mut lines = Lines::new(day, input);
+    for (row, line) in lines.by_ref().enumerate() {
+        for (col, c) in line.text.chars().enumerate() {
+            if c != marker {
+                continue;
             }
-        }
-        output.map.push(temp);
-    }
-    output
-}
-
-fn neighbors(pos: (usize, usize), map: &HeightMap) -> Vec<(usize, usize)> {
-    let w = map.map[0].len();
-    let h = map.map.len();
-    let height = map.height(pos);
-    let mut output = vec![];
-    //up
-    if pos.1 > 0 {
-        let other = (pos.0, pos.1 - 1);
-        let hother = map.height(other);
-        if hother <= height + 1 {
-            output.push(other);
-        }
-    }
-    //down
-    if pos.1 < h - 1 {
-        let other = (pos.0, pos.1 + 1);
-        let hother = map.height(other);
-        if hother <= height + 1 {
-            output.push(other);
-        }
-    }
-    //left
-    if pos.0 > 0 {
-        let other = (pos.0 - 1, pos.1);
-        let hother = map.height(other);
-        if hother <= height + 1 {
-            output.push(other);
+            if let Some((first, _)) = found {
+                let expected = format!("only one `{}`, after line {}", marker, first);
+                return Err(line.error_at(col + 1, &expected));
+            }
+            found = Some((line.number, Point2::new(col, row)));
         }
     }
-    //right
-    if pos.0 < w - 1 {
-        let other = (pos.0 + 1, pos.1);
-        let hother = map.height(other);
-        if hother <= height + 1 {
-            output.push(other);
-        }
+    match found {
+        Some((_, p)) => Ok(p),
+        None => Err(lines
+            .next_or(&format!("one `{}` somewhere on the map", marker))
+            .unwrap_err()),
     }
-    output
 }
 
-fn solve(start: (usize, usize), input: &Input) -> usize {
-    let mut paths: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
-    let mut investigate = HashSet::new();
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
-
-    let end = input.end_pos();
-    investigate.insert(start);
-    paths.insert(start, vec![start]);
-    loop {
-        let mut investigate_next: HashSet<(usize, usize)> = HashSet::new();
-
-        // Iterate over points to investigate
-        for trial in &investigate {
-            let neighbors = neighbors(*trial, input);
-
-            // Iterate over neighbors of trial point
-            let curr_path = paths.get(trial).unwrap().clone();
-            for n in neighbors {
-                if let Some(p) = paths.get_mut(&n) {
-                    // If there exists a path to n already, see if this one is
-                    // shorter, insert if it is.
-                    if p.len() > curr_path.len() + 1 {
-                        let mut tpath = curr_path.clone();
-                        tpath.push(n);
-                        *p = tpath;
-                    }
-                } else {
-                    // If there is no path to n already, use this one
-                    let mut tpath = curr_path.clone();
-                    tpath.push(n);
-                    paths.insert(n, tpath);
-                }
-
-                // Visit n if we haven't already
-                if !visited.contains(&n) {
-                    investigate_next.insert(n);
-                }
-            }
-
-            // Put trial in visited if it isn't already there
-            visited.insert(*trial);
-        }
+fn load_input(input: &str) -> Result<Input, ParseError> {
+    let map = Grid::from_str(12, input, "a height `a`-`z`, `S` or `E`", |c| match c {
+        'S' => Some(Tile::Start),
+        'E' => Some(Tile::End),
+        'a'..='z' => Some(Tile::Value((c as i32) - 97)),
+        _ => None,
+    })?;
+    let start = find_one(12, input, 'S')?;
+    let end = find_one(12, input, 'E')?;
+    Ok(HeightMap { map, start, end })
+}
 
-        if investigate_next.is_empty() {
-            break;
-        } else {
-            investigate = investigate_next.into_iter().collect();
-        }
-    }
+/// Steps up at most one level at a time.
+impl Connected for HeightMap {
+    type Node = Point2<usize>;
 
-    if paths.contains_key(&end) {
-        paths.get(&end).unwrap().len() - 1
-    } else {
-        999999999
+    fn neighbours(&self, pos: &Point2<usize>) -> Vec<Point2<usize>> {
+        let height = self.height(*pos);
+        self.map
+            .neighbours4(*pos)
+            .filter(|&other| self.height(other) <= height + 1)
+            .collect()
     }
 }
 
-#[aoc(day12, part1)]
 fn part1(input: &Input) -> usize {
-    let start = input.start_pos();
-    solve(start, input)
+    let path = shortest_path(input, input.start_pos(), &input.end_pos());
+    path.expect("no way to the top").len() - 1
 }
 
-#[aoc(day12, part2)]
 fn part2(input: &Input) -> usize {
-    let mut starts: Vec<(usize, usize)> = vec![];
-    for (r, row) in input.map.iter().enumerate() {
-        for (c, col) in row.iter().enumerate() {
-            if let Tile::Value(0) = col {
-                starts.push((c, r));
-            }
-        }
+    // Setting off from every lowest point at once finds the nearest one
+    let starts = input
+        .map
+        .iter()
+        .filter(|(_, &tile)| tile == Tile::Start || tile == Tile::Value(0))
+        .map(|(p, _)| p);
+    let paths = multi_bfs(input, starts);
+    paths.distance(&input.end_pos()).expect("no way to the top") as usize
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
     }
 
-    let mut lengths = vec![];
-    for start in starts {
-        lengths.push(solve(start, input));
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
     }
-    lengths.sort();
-    lengths[0]
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/12.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(12, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 31);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/12.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(12, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 29);
     }
+
+    #[test]
+    fn test_load_input() {
+        let input = load_input("Sab\nabE\n").unwrap();
+        assert_eq!(
+            (input.start_pos(), input.end_pos()),
+            ((0, 0).into(), (2, 1).into())
+        );
+        let error = load_input("abc\nabd\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (3, "one `S` somewhere on the map")
+        );
+        let error = load_input("Sbc\nabd\n").err().unwrap();
+        assert_eq!(error.expected, "one `E` somewhere on the map");
+        let error = load_input("SbE\naSd\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.expected, "only one `S`, after line 1");
+    }
 }
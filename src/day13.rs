@@ -1,3 +1,5 @@
+use crate::parse::{Line, Lines, ParseError};
+use crate::solution::Solution;
 use std::cmp::Ordering;
 use std::collections::VecDeque;
 
@@ -39,15 +41,7 @@ impl Ord for Atom {
 
 impl PartialOrd for Atom {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        if let Some(b) = self.compare(other) {
-            if b {
-                Some(Ordering::Less)
-            } else {
-                Some(Ordering::Greater)
-            }
-        } else {
-            Some(Ordering::Equal)
-        }
+        Some(self.cmp(other))
     }
 }
 
@@ -146,7 +140,7 @@ fn parse_string(s: &str) -> Atom {
 }
 
 #[derive(Clone, Debug, PartialEq)]
-struct PacketPair {
+pub struct PacketPair {
     p1: String,
     p2: String,
 }
@@ -161,23 +155,63 @@ impl PacketPair {
 
 type Input = PacketPair;
 
-#[aoc_generator(day13)]
-fn load_input(input: &str) -> Vec<Input> {
+/// Check that `line` is one well formed packet: a list of numbers and lists,
+/// with every bracket closed and no empty elements.
+fn packet(line: Line) -> Result<String, ParseError> {
+    let text = line.text;
+    if !text.starts_with('[') {
+        return Err(line.error_at(1, "`[`"));
+    }
+    let mut depth = 0;
+    // Whether the last thing seen finished an element, so a `,` or `]`
+    // may follow, rather than opening a list or following a comma
+    let mut after_element = false;
+    let mut digits = None;
+    for (i, c) in text.char_indices() {
+        if let Some(start) = digits.filter(|_| !c.is_ascii_digit()) {
+            line.parse::<usize>(&text[start..i], "a number")?;
+            digits = None;
+        }
+        if depth == 0 && i > 0 {
+            return Err(line.error_at(i + 1, "the end of the packet"));
+        }
+        match c {
+            '[' if !after_element => depth += 1,
+            ']' if after_element || text[..i].ends_with('[') => {
+                depth -= 1;
+                after_element = true;
+            }
+            ',' if after_element => after_element = false,
+            '0'..='9' if digits.is_some() || !after_element => {
+                digits.get_or_insert(i);
+                after_element = true;
+            }
+            _ if after_element => return Err(line.error_at(i + 1, "`,` or `]`")),
+            _ if text[..i].ends_with(',') => return Err(line.error_at(i + 1, "`[` or a digit")),
+            _ => return Err(line.error_at(i + 1, "`[`, `]` or a digit")),
+        }
+    }
+    if depth > 0 {
+        return Err(line.error_at(text.len() + 1, "`]`"));
+    }
+    Ok(String::from(text))
+}
+
+fn load_input(input: &str) -> Result<Vec<Input>, ParseError> {
     let mut output = vec![];
-    let mut line = input.lines();
+    let mut line = Lines::new(13, input);
 
     loop {
-        let p1 = String::from(line.next().unwrap());
-        let p2 = String::from(line.next().unwrap());
+        let p1 = packet(line.next_or("a packet")?)?;
+        let p2 = packet(line.next_or("a second packet")?)?;
         output.push(PacketPair { p1, p2 });
         if line.next().is_none() {
             break;
         }
     }
-    output
+    Ok(output)
 }
 
-#[aoc(day13, part1)]
 fn part1(input: &[Input]) -> usize {
     let mut idxs = vec![];
     for (i, line) in input.iter().enumerate() {
@@ -188,7 +222,6 @@ fn part1(input: &[Input]) -> usize {
     idxs.iter().sum::<usize>()
 }
 
-#[aoc(day13, part2)]
 fn part2(input: &[Input]) -> usize {
     let mut lines = vec![];
     for pair in input {
@@ -216,29 +249,68 @@ fn part2(input: &[Input]) -> usize {
     idxs[0] * idxs[1]
 }
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<Input>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/13.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(13, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 13);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/13.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(13, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 140);
     }
 
+    #[test]
+    fn test_packet() {
+        let column = |text: &str| {
+            let pair = format!("[]\n{}\n", text);
+            load_input(&pair).unwrap_err().column
+        };
+        assert_eq!(column("[1,[2"), 6);
+        assert_eq!(column("[1,,2]"), 4);
+        assert_eq!(column("[1,]"), 4);
+        assert_eq!(column("[,1]"), 2);
+        assert_eq!(column("[1][2]"), 4);
+        assert_eq!(column("[1[2]]"), 3);
+        assert_eq!(column("[[1]2]"), 5);
+        assert_eq!(column("[1]]"), 4);
+        assert_eq!(column("[1,x]"), 4);
+        assert_eq!(column("[99999999999999999999999]"), 2);
+        assert!(load_input("[[],[1,[10]]]\n[]\n").is_ok());
+    }
+
     #[test]
     fn test_chris() {
-        let input = read_to_string("input/2022/chris_day13.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(13, &InputKind::Named(String::from("chris"))).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 5760);
     }
 }
@@ -1,156 +1,143 @@
+use crate::geom::{Direction8, Point2};
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
+use crate::sparse::{Bounds, SparseGrid};
 use scan_fmt::scan_fmt;
-use std::collections::HashSet;
 
-type Input = Vec<(usize, usize)>;
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Material {
+    Rock,
+    Sand,
+}
 
-// let input_iter = input.iter();
-// loop {
-//     let line = input_iter.next().unwrap();
-// }
-//
-// let comma_separated = line.split(',').collect();
+type Input = Vec<Point2<i64>>;
 
-#[aoc_generator(day14)]
-fn load_input(input: &str) -> Vec<Input> {
+fn load_input(input: &str) -> Result<Vec<Input>, ParseError> {
     let mut output = vec![];
-    for line in input.lines() {
+    for line in Lines::new(14, input) {
         let mut temp = vec![];
-        for pair_str in line.split(" -> ") {
-            let (x, y) = scan_fmt!(pair_str, "{},{}", usize, usize).unwrap();
-            temp.push((x, y));
+        for pair_str in line.text.split(" -> ") {
+            let (x, y) = scan_fmt!(pair_str, "{},{}", i64, i64)
+                .map_err(|_| line.scan_error_in(pair_str, "{},{}"))?;
+            temp.push(Point2::new(x, y));
         }
         output.push(temp);
     }
-    output
+    Ok(output)
 }
 
-fn draw_line(p1: &(usize, usize), p2: &(usize, usize)) -> HashSet<(usize, usize)> {
-    let mut output = HashSet::new();
-    if p1.0 == p2.0 {
-        let mut miny = p1.1;
-        let mut maxy = p2.1;
-        if p2.1 < miny {
-            miny = p2.1;
-            maxy = p1.1
-        }
-
-        for i in miny..maxy + 1 {
-            output.insert((p1.0, i));
-        }
-    } else {
-        let mut minx = p1.0;
-        let mut maxx = p2.0;
-        if p2.0 < minx {
-            minx = p2.0;
-            maxx = p1.0
-        }
-
-        for i in minx..maxx + 1 {
-            output.insert((i, p1.1));
-        }
-    }
-    output
-}
-
-fn draw(input: &[Input]) -> HashSet<(usize, usize)> {
-    let mut output: HashSet<(usize, usize)> = HashSet::new();
+fn draw(input: &[Input]) -> SparseGrid<Material> {
+    let mut output = SparseGrid::new();
     for line in input {
-        for i in 0..line.len() - 1 {
-            let line_set = draw_line(&line[i], &line[i + 1]);
-            output = output.union(&line_set).cloned().collect();
+        for ends in line.windows(2) {
+            // Lines are straight, so the box around the ends is the line
+            for p in Bounds::new(ends[0], ends[1]).points() {
+                output.insert(p, Material::Rock);
+            }
         }
     }
-
     output
 }
 
-fn iterate_sand(
-    pos: (usize, usize),
-    sand: &HashSet<(usize, usize)>,
-    rock: &HashSet<(usize, usize)>,
-) -> Option<(usize, usize)> {
-    if !rock.contains(&(pos.0, pos.1 + 1)) && !sand.contains(&(pos.0, pos.1 + 1)) {
-        Some((pos.0, pos.1 + 1))
-    } else if !rock.contains(&(pos.0 - 1, pos.1 + 1)) && !sand.contains(&(pos.0 - 1, pos.1 + 1)) {
-        Some((pos.0 - 1, pos.1 + 1))
-    } else if !rock.contains(&(pos.0 + 1, pos.1 + 1)) && !sand.contains(&(pos.0 + 1, pos.1 + 1)) {
-        Some((pos.0 + 1, pos.1 + 1))
-    } else {
-        None
-    }
+/// Where the grain at `pos` falls next: straight down if it can, otherwise
+/// diagonally left, then diagonally right.
+fn iterate_sand(pos: Point2<i64>, cave: &SparseGrid<Material>) -> Option<Point2<i64>> {
+    [
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::SouthEast,
+    ]
+    .into_iter()
+    .map(|dir| pos.step(dir))
+    .find(|&next| !cave.contains(next))
 }
 
-fn sim_sand(
-    sand: &mut HashSet<(usize, usize)>,
-    rock: &HashSet<(usize, usize)>,
-    lowest: usize,
-    part2: bool,
-) -> Option<(usize, usize)> {
-    let entry = (500, 0);
+fn sim_sand(cave: &SparseGrid<Material>, lowest: i64, part2: bool) -> Option<Point2<i64>> {
+    let entry = Point2::new(500, 0);
     let mut pos = entry;
 
-    while let Some(new_pos) = iterate_sand(pos, sand, rock) {
+    while let Some(new_pos) = iterate_sand(pos, cave) {
         pos = new_pos;
         if !part2 {
-            if pos.1 >= lowest {
+            if pos.y >= lowest {
                 // Fell into the abyss
                 return None;
             }
-        } else if pos.1 == lowest - 1 {
+        } else if pos.y == lowest - 1 {
             return Some(pos);
         }
     }
     Some(pos)
 }
 
-#[aoc(day14, part1)]
+fn count_sand(cave: &SparseGrid<Material>) -> usize {
+    cave.iter().filter(|&(_, &m)| m == Material::Sand).count()
+}
+
 fn part1(input: &[Input]) -> usize {
-    let rock = draw(input);
-    let mut sand = HashSet::new();
+    let mut cave = draw(input);
 
-    let lowest = rock.iter().map(|r| r.1).max().unwrap();
+    let lowest = cave.bounds().unwrap().max.y;
 
-    while let Some(new_sand) = sim_sand(&mut sand, &rock, lowest, false) {
-        sand.insert(new_sand);
+    while let Some(new_sand) = sim_sand(&cave, lowest, false) {
+        cave.insert(new_sand, Material::Sand);
     }
-    sand.len()
+    count_sand(&cave)
 }
 
-#[aoc(day14, part2)]
 fn part2(input: &[Input]) -> usize {
-    let rock = draw(input);
-    let mut sand = HashSet::new();
+    let mut cave = draw(input);
 
-    let lowest = rock.iter().map(|r| r.1).max().unwrap() + 2;
+    let lowest = cave.bounds().unwrap().max.y + 2;
 
-    let mut last_sand = (500, 0);
-    while let Some(new_sand) = sim_sand(&mut sand, &rock, lowest, true) {
+    let mut last_sand = Point2::new(500, 0);
+    while let Some(new_sand) = sim_sand(&cave, lowest, true) {
         if new_sand == last_sand {
             break;
         } else {
             last_sand = new_sand;
         }
-        sand.insert(new_sand);
+        cave.insert(new_sand, Material::Sand);
+    }
+    count_sand(&cave)
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Input>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
     }
-    sand.len()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/14.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(14, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 24);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/14.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(14, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 93);
     }
 }
@@ -1,38 +1,36 @@
+use crate::geom::Point2;
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
 use scan_fmt::scan_fmt;
 use std::collections::{HashMap, HashSet};
 
-type Input = (Vec<(i64, i64)>, Vec<(i64, i64)>);
+type Input = (Vec<Point2<i64>>, Vec<Point2<i64>>);
 
-#[aoc_generator(day15)]
-fn load_input(input: &str) -> Input {
+fn load_input(input: &str) -> Result<Input, ParseError> {
     let mut sensors = vec![];
     let mut beacons = vec![];
-    for line in input.lines() {
+    for line in Lines::new(15, input) {
         let (x1, y1, x2, y2) = scan_fmt!(
-            line,
+            line.text,
             "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
             i64,
             i64,
             i64,
             i64
         )
-        .unwrap();
-        sensors.push((x1, y1));
-        beacons.push((x2, y2));
+        .map_err(|_| line.scan_error("Sensor at x={}, y={}: closest beacon is at x={}, y={}"))?;
+        sensors.push(Point2::new(x1, y1));
+        beacons.push(Point2::new(x2, y2));
     }
-    (sensors, beacons)
+    Ok((sensors, beacons))
 }
 
-fn mdist(p1: (i64, i64), p2: (i64, i64)) -> i64 {
-    (p1.0 - p2.0).abs() + (p1.1 - p2.1).abs()
-}
-
-fn empty_on_line(sensor: (i64, i64), beacon: (i64, i64), line: i64) -> Vec<i64> {
+fn empty_on_line(sensor: Point2<i64>, beacon: Point2<i64>, line: i64) -> Vec<i64> {
     let mut output = vec![];
-    let md = mdist(sensor, beacon);
-    let yoffset = (sensor.1 - line).abs();
-    let start = sensor.0 - md + yoffset;
-    let end = sensor.0 + md - yoffset;
+    let md = sensor.manhattan(beacon);
+    let yoffset = (sensor.y - line).abs();
+    let start = sensor.x - md + yoffset;
+    let end = sensor.x + md - yoffset;
     for i in start..end {
         output.push(i);
     }
@@ -50,25 +48,24 @@ fn _part1(input: &Input, line: i64) -> i64 {
     empty.len().try_into().unwrap()
 }
 
-#[aoc(day15, part1)]
 fn part1(input: &Input) -> i64 {
     _part1(input, 2000000)
 }
 
 /// Line format (slope, yintercept)
-fn lines_from_sensor(sensor: (i64, i64), dist: i64) -> Vec<(i64, i64)> {
+fn lines_from_sensor(sensor: Point2<i64>, dist: i64) -> Vec<(i64, i64)> {
     vec![
-        (-1, sensor.1 + dist + 1 + sensor.0), // NE
-        (1, sensor.1 - dist - 1 - sensor.0),  // SE
-        (-1, sensor.1 - dist - 1 + sensor.0), // SW
-        (1, sensor.1 + dist + 1 - sensor.0),  // NW
+        (-1, sensor.y + dist + 1 + sensor.x), // NE
+        (1, sensor.y - dist - 1 - sensor.x),  // SE
+        (-1, sensor.y - dist - 1 + sensor.x), // SW
+        (1, sensor.y + dist + 1 - sensor.x),  // NW
     ]
 }
 
-fn line_intersection(nline: (i64, i64), pline: (i64, i64)) -> (i64, i64) {
+fn line_intersection(nline: (i64, i64), pline: (i64, i64)) -> Point2<i64> {
     let x = (nline.1 - pline.1) / 2;
     let y = x + pline.1;
-    (x, y)
+    Point2::new(x, y)
 }
 
 fn _part2(input: &Input, size: usize) -> i64 {
@@ -77,7 +74,7 @@ fn _part2(input: &Input, size: usize) -> i64 {
     let mdists: Vec<_> = sensors
         .iter()
         .zip(beacons.iter())
-        .map(|(&s, &b)| mdist(s, b))
+        .map(|(&s, &b)| s.manhattan(b))
         .collect();
 
     let mut all_lines: HashMap<(i64, i64), usize> = HashMap::new();
@@ -96,7 +93,7 @@ fn _part2(input: &Input, size: usize) -> i64 {
     for nline in nlines {
         for pline in &plines {
             let cpt = line_intersection(*nline, **pline);
-            if cpt.0 >= 0 && cpt.1 >= 0 && cpt.0 <= size as i64 && cpt.1 <= size as i64 {
+            if cpt.x >= 0 && cpt.y >= 0 && cpt.x <= size as i64 && cpt.y <= size as i64 {
                 candidates.push(cpt);
             }
         }
@@ -105,40 +102,59 @@ fn _part2(input: &Input, size: usize) -> i64 {
     for c in candidates {
         let mut flag = true;
         for i in 0..sensors.len() {
-            if mdist(c, sensors[i]) <= mdists[i] {
+            if c.manhattan(sensors[i]) <= mdists[i] {
                 flag = false;
                 break;
             }
         }
 
         if flag {
-            return c.0 * 4000000 + c.1;
+            return c.x * 4000000 + c.y;
         }
     }
     unreachable!();
 }
 
-#[aoc(day15, part2)]
 fn part2(input: &Input) -> i64 {
     _part2(input, 4000000)
 }
 
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Input;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/15.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(15, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(_part1(&input, 10), 26);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/15.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(15, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(_part2(&input, 20), 56000011);
     }
 }
@@ -1,99 +1,76 @@
-use aoc_helpers::graph::{shortest_path, Connected};
-use itertools::Itertools;
-use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
+use crate::intern::Interner;
+use crate::optimize::{maximize, Problem};
+use crate::parse::{Lines, ParseError};
+use crate::search::{bfs, Connected};
+use crate::solution::Solution;
+use log::debug;
+use std::collections::HashMap;
 
 type Input = (String, i64, Vec<String>);
 
-#[derive(Clone, Debug)]
-struct UniqueMapping {
-    word_to_int_map: HashMap<String, usize>,
-    int_to_word_map: HashMap<usize, String>,
-    cntr: usize,
-}
-
-impl UniqueMapping {
-    fn new() -> Self {
-        Self {
-            word_to_int_map: HashMap::new(),
-            int_to_word_map: HashMap::new(),
-            cntr: 0,
-        }
-    }
+/// Valves are named in the input, and numbered by an `Interner`.
+type Valve = u16;
 
-    fn push(&mut self, word: &str) -> usize {
-        let int = self.cntr;
-        self.cntr += 1;
-        self.word_to_int_map.insert(word.to_string(), int);
-        self.int_to_word_map.insert(int, word.to_string());
-        int
-    }
-
-    fn word_to_int(&self, word: &str) -> usize {
-        if let Some(int) = self.word_to_int_map.get(word) {
-            *int
-        } else {
-            panic!("word {} not found in mapping!", word);
-        }
-    }
-
-    fn int_to_word(&self, int: usize) -> String {
-        if let Some(word) = self.int_to_word_map.get(&int) {
-            word.to_string()
-        } else {
-            panic!("int {} not found in mapping!", int);
-        }
-    }
-}
-
-fn path_to_string(path: &[usize], mapping: &UniqueMapping) -> String {
-    let strvec: Vec<_> = path.iter().map(|&x| mapping.int_to_word(x)).collect();
-    if strvec.is_empty() {
-        String::new()
-    } else {
-        let mut output = strvec[0].to_string();
-        for node in &strvec[1..] {
-            output.push_str(" -> ");
-            output.push_str(node);
-        }
-        output
-    }
+/// The id of a valve named in the input. `load_input` checks that every
+/// tunnel leads to one of them, and that there is an `AA`.
+fn valve(mapping: &Interner<Valve>, name: &str) -> Valve {
+    mapping
+        .get(name)
+        .unwrap_or_else(|| panic!("no valve named {}", name))
 }
 
-fn populate_word_mapping(lines: &[Input]) -> UniqueMapping {
-    let mut mapping = UniqueMapping::new();
+fn populate_word_mapping(lines: &[Input]) -> Interner<Valve> {
+    let mut mapping = Interner::new();
     for (node, _, _) in lines {
-        mapping.push(node);
+        mapping.get_or_intern(node);
     }
     mapping
 }
 
-#[aoc_generator(day16)]
-fn load_input(input: &str) -> Vec<Input> {
+fn load_input(input: &str) -> Result<Vec<Input>, ParseError> {
     let mut output = vec![];
-    for line in input.lines() {
-        let words: Vec<&str> = line.split(' ').collect();
+    let mut tunnels = vec![];
+    let mut rated = 0;
+    let mut lines = Lines::new(16, input);
+    for line in lines.by_ref() {
+        let words: Vec<&str> = line.text.split(' ').collect();
+        if words.len() < 10 {
+            let expected = "`Valve XX has flow rate=N; tunnels lead to valves ...`";
+            return Err(line.error_at(1, expected));
+        }
         let valve = words[1].to_string();
-        let rate = words[4];
-        let rate: Vec<_> = rate.split('=').collect();
-        let rate: Vec<_> = rate[1].split(';').collect();
-        let rate = rate[0].parse::<i64>().unwrap();
-        let others: Vec<_> = words[9..]
+        let rate_text = words[4].trim_start_matches("rate=").trim_end_matches(';');
+        let rate = line.parse::<i64>(rate_text, "a flow rate")?;
+        if rate > 0 {
+            // Open valves are kept as bits in a u64
+            rated += 1;
+            if rated > 64 {
+                return Err(line.error_in(rate_text, "at most 64 valves with a flow rate"));
+            }
+        }
+        let others: Vec<&str> = words[9..]
             .iter()
-            .map(|s| {
-                let temp: Vec<_> = s.split(',').collect();
-                temp[0].to_string()
-            })
+            .map(|s| s.split(',').next().unwrap())
             .collect();
-
-        let pline = (valve, rate, others);
+        let pline = (valve, rate, others.iter().map(|s| s.to_string()).collect());
+        tunnels.push((line, others));
         output.push(pline);
     }
-    output
+
+    let mapping = populate_word_mapping(&output);
+    for (line, others) in tunnels {
+        if let Some(name) = others.into_iter().find(|name| mapping.get(name).is_none()) {
+            return Err(line.error_in(name, "a valve listed in the input"));
+        }
+    }
+    if mapping.get("AA").is_none() {
+        return Err(lines.next_or("a line for valve `AA`").unwrap_err());
+    }
+    Ok(output)
 }
 
 #[derive(Clone, Debug)]
-struct ValveIntMap(HashMap<usize, (i64, Vec<usize>, bool)>);
+struct ValveIntMap(HashMap<Valve, (i64, Vec<Valve>)>);
 
 impl ValveIntMap {
     fn new() -> Self {
@@ -102,347 +79,258 @@ impl ValveIntMap {
 }
 
 impl Connected for ValveIntMap {
-    type Item = usize;
-    fn get_neighbors(&self, node: &Self::Item) -> Vec<Self::Item> {
+    type Node = Valve;
+    fn neighbours(&self, node: &Self::Node) -> Vec<Self::Node> {
         self.0.get(node).unwrap().1.clone()
     }
 }
 
-fn value_added(valve: usize, time: i64, rate: i64, nodes_enabled: &HashMap<usize, bool>) -> i64 {
-    let enabled = nodes_enabled.get(&valve).unwrap();
-    if !enabled {
-        rate * time
-    } else {
-        0
+/// The valves worth opening, and how long it takes to walk between them.
+struct Tunnels {
+    start: Valve,
+    /// Every valve with a flow rate, and the rate. A valve's place in this
+    /// list is its bit in a set of open valves.
+    rates: Vec<(Valve, i64)>,
+    /// Minutes from one valve to another, for the start and every valve
+    /// with a flow rate.
+    distance: HashMap<(Valve, Valve), i64>,
+}
+
+impl Tunnels {
+    fn new(input: &[Input]) -> Self {
+        let mapping = populate_word_mapping(input);
+        let mut nodes = ValveIntMap::new();
+        for (name, rate, tunnels) in input {
+            let tunnels = tunnels.iter().map(|k| valve(&mapping, k)).collect();
+            nodes.0.insert(valve(&mapping, name), (*rate, tunnels));
+        }
+
+        let start = valve(&mapping, "AA");
+        let mut rates: Vec<(Valve, i64)> = nodes
+            .0
+            .iter()
+            .filter(|(_, (rate, _))| *rate > 0)
+            .map(|(&v, (rate, _))| (v, *rate))
+            .collect();
+        rates.sort();
+
+        let mut distance = HashMap::new();
+        for from in rates.iter().map(|r| r.0).chain([start]) {
+            let paths = bfs(&nodes, from);
+            for &(to, _) in &rates {
+                if let Some(d) = paths.distance(&to) {
+                    distance.insert((from, to), d as i64);
+                }
+            }
+        }
+        Self {
+            start,
+            rates,
+            distance,
+        }
+    }
+
+    /// Minutes left once `to` is reached from `from` and opened, if it can
+    /// be reached at all.
+    fn open_at(&self, from: Valve, to: Valve, time_left: i64) -> Option<i64> {
+        let d = self.distance.get(&(from, to))?;
+        Some(time_left - d - 1)
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug)]
 struct State {
-    current_node: usize,
+    /// Which of us is walking. The others go in turn once they're done.
+    walker: usize,
+    at: Valve,
     time_left: i64,
-    total_value: i64,
-    nodes_enabled: HashMap<usize, bool>,
-}
-
-/// Base step in our graph traversal
-fn goto_and_enable_node(
-    node: usize,
-    state: &mut State,
-    rates: &HashMap<usize, i64>,
-    path_lengths: &HashMap<(usize, usize), i64>,
-) {
-    let time_walking = path_lengths.get(&(node, state.current_node)).unwrap();
-    let this_time_left = state.time_left - time_walking - 1;
-    let rate = *rates.get(&node).unwrap();
-    let value = value_added(node, this_time_left, rate, &state.nodes_enabled);
-
-    state.current_node = node;
-    *state.nodes_enabled.get_mut(&state.current_node).unwrap() = true;
-    state.total_value += value;
-    state.time_left = this_time_left;
+    /// The valves opened so far, as bits by their place in `rates`.
+    open: u64,
+    /// Pressure the open valves will let out by the end.
+    released: i64,
 }
 
-fn path_to_state(
-    path: &[usize],
-    rates: &HashMap<usize, i64>,
-    nodes_enabled: &HashMap<usize, bool>,
-    path_lengths: &HashMap<(usize, usize), i64>,
-    mapping: &UniqueMapping,
-) -> State {
-    let mut state = State {
-        current_node: mapping.word_to_int("AA"),
-        time_left: 30,
-        total_value: 0,
-        nodes_enabled: nodes_enabled.clone(),
-    };
-    for n in path {
-        goto_and_enable_node(*n, &mut state, rates, path_lengths);
-    }
-    state
+/// The most pressure `walkers` of us can release in `minutes`. Each
+/// decision is the next valve to open, or to stop and let the next walker
+/// start from the beginning.
+struct Release<'a> {
+    tunnels: &'a Tunnels,
+    walkers: usize,
+    minutes: i64,
 }
 
-type PathState = (usize, i64);
-
-fn paths_from_state(
-    state: PathState,
-    nodes: &HashSet<usize>,
-    path_lengths: &HashMap<(usize, usize), i64>,
-) -> Vec<PathState> {
-    let mut output = vec![];
-    for node in nodes {
-        let length = path_lengths.get(&(*node, state.0)).unwrap();
-        let next_time = state.1 - length - 1;
-        if next_time >= 0 && next_time < state.1 {
-            output.push((*node, next_time));
+impl Release<'_> {
+    fn start(&self) -> State {
+        State {
+            walker: 0,
+            at: self.tunnels.start,
+            time_left: self.minutes,
+            open: 0,
+            released: 0,
         }
     }
-    output
 }
 
-fn create_path_lengths(
-    possible_nodes: &HashSet<usize>,
-    nodes: &ValveIntMap,
-) -> HashMap<(usize, usize), i64> {
-    let mut path_lengths = HashMap::new();
-    for node1 in possible_nodes {
-        for node2 in possible_nodes {
-            if let Some(path) = shortest_path(node1, node2, nodes) {
-                path_lengths.insert((*node1, *node2), path.len() as i64 - 1);
+impl Problem for Release<'_> {
+    type State = State;
+    type Key = (usize, Valve, i64, u64);
+
+    fn branch(&self, state: &State) -> Vec<State> {
+        let mut next = vec![];
+        for (i, &(v, rate)) in self.tunnels.rates.iter().enumerate() {
+            if state.open & 1 << i != 0 {
+                continue;
+            }
+            match self.tunnels.open_at(state.at, v, state.time_left) {
+                Some(time_left) if time_left > 0 => next.push(State {
+                    at: v,
+                    time_left,
+                    open: state.open | 1 << i,
+                    released: state.released + rate * time_left,
+                    ..*state
+                }),
+                _ => (),
             }
         }
-    }
-    path_lengths
-}
-
-#[aoc(day16, part1)]
-fn part1(input: &[Input]) -> i64 {
-    let mapping = populate_word_mapping(input);
-    let mut nodes = ValveIntMap::new();
-    for line in input {
-        nodes.0.insert(
-            mapping.word_to_int(&line.0),
-            (
-                line.1,
-                line.2
-                    .iter()
-                    .map(|k| mapping.word_to_int(k))
-                    .collect::<Vec<_>>(),
-                false,
-            ),
-        );
-    }
-    let rates: HashMap<usize, i64> = nodes.clone().0.iter().map(|(&k, v)| (k, v.0)).collect();
-    let nodes_enabled: HashMap<usize, bool> =
-        nodes.clone().0.iter().map(|(&k, v)| (k, v.2)).collect();
-    let possible_nodes: HashSet<usize> = nodes.0.keys().cloned().collect();
-    let path_lengths = create_path_lengths(&possible_nodes, &nodes);
-    let good_nodes: HashSet<usize> = possible_nodes
-        .into_iter()
-        .filter(|n| nodes.0.get(n).unwrap().0 > 0)
-        .collect();
-
-    // For each useful end state...
-    let mut fwdprop_map: HashMap<PathState, Vec<PathState>> = HashMap::new();
-    for new_node in &good_nodes {
-        for time_left in 0..30 {
-            let state = (*new_node, time_left as i64);
-            let paths_from = paths_from_state(state, &good_nodes, &path_lengths);
-            fwdprop_map.insert(state, paths_from);
+        if state.walker + 1 < self.walkers {
+            next.push(State {
+                walker: state.walker + 1,
+                at: self.tunnels.start,
+                time_left: self.minutes,
+                ..*state
+            });
         }
+        next
+    }
 
-        let state = (mapping.word_to_int("AA"), 30);
-        let paths_from = paths_from_state(state, &good_nodes, &path_lengths);
-        fwdprop_map.insert(state, paths_from);
+    fn value(&self, state: &State) -> i64 {
+        state.released
     }
 
-    let mut valid_paths = HashSet::<Vec<PathState>>::new();
-    let start = (mapping.word_to_int("AA"), 30);
-    valid_paths.insert(vec![start]);
-
-    let mut cntr = 0;
-    loop {
-        println!("iter {}", cntr);
-        cntr += 1;
-        let mut new_paths = HashSet::new();
-        for path in &valid_paths {
-            let last_node = path.last().unwrap();
-            if let Some(states) = fwdprop_map.get(last_node) {
-                for state in states {
-                    if !path.iter().map(|p| p.0).contains(&state.0) {
-                        let mut tpath = path.clone();
-                        tpath.push(*state);
-                        new_paths.insert(tpath);
-                    }
-                }
+    /// As if every closed valve were opened by whoever could get there
+    /// soonest, without anyone losing time on the others.
+    fn bound(&self, state: &State) -> i64 {
+        let more_walkers = state.walker + 1 < self.walkers;
+        let mut bound = state.released;
+        for (i, &(v, rate)) in self.tunnels.rates.iter().enumerate() {
+            if state.open & 1 << i != 0 {
+                continue;
             }
-        }
-
-        if new_paths.is_subset(&valid_paths) {
-            break;
-        } else {
-            for path in new_paths {
-                valid_paths.insert(path);
+            let mut best = self.tunnels.open_at(state.at, v, state.time_left);
+            if more_walkers {
+                best = best.max(self.tunnels.open_at(self.tunnels.start, v, self.minutes));
             }
+            bound += rate * best.unwrap_or(0).max(0);
         }
+        bound
     }
 
-    println!("valid_paths.len(): {}", valid_paths.len());
-
-    let mut values = vec![];
-    for path in valid_paths {
-        let int_path: Vec<usize> = path.iter().skip(1).map(|p| p.0).collect();
-        let state = path_to_state(&int_path, &rates, &nodes_enabled, &path_lengths, &mapping);
-        values.push((state.total_value, int_path));
-    }
-
-    values.sort_by(|a, b| a.0.cmp(&b.0));
-    for v in &values {
-        println!("{}, {}", v.0, path_to_string(&v.1, &mapping));
+    fn key(&self, state: &State) -> Self::Key {
+        (state.walker, state.at, state.time_left, state.open)
     }
-    values.last().unwrap().0
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Agent<'a> {
-    node: usize,
-    goal: Option<usize>,
-    time_left: i64,
-    valve_map: &'a ValveIntMap,
-    value: i64,
+fn most_pressure(input: &[Input], walkers: usize, minutes: i64) -> i64 {
+    let tunnels = Tunnels::new(input);
+    let problem = Release {
+        tunnels: &tunnels,
+        walkers,
+        minutes,
+    };
+    let best = maximize(&problem, problem.start());
+    debug!("{:?}", best.stats);
+    best.value
 }
 
-impl<'a> Agent<'a> {
-    fn new(time_left: i64, start_node: usize, valve_map: &'a ValveIntMap) -> Self {
-        Self {
-            node: start_node,
-            goal: None,
-            time_left,
-            valve_map: valve_map,
-            value: 0,
-        }
-    }
+fn part1(input: &[Input]) -> i64 {
+    most_pressure(input, 1, 30)
+}
 
-    /// Returns true if new goal needs set, false otherwise
-    fn time_step(&mut self, valves_enabled: &mut HashMap<usize, bool>) -> bool {
-        if self.time_left > 0 {
-            if Some(self.node) == self.goal && !valves_enabled.get(&self.node).unwrap() {
-                // If we're at the goal and it's not already on, turn on valve
-                // and get our value for it.
-                *valves_enabled.get_mut(&self.node).unwrap() = true;
-                let new_value = (self.time_left - 1) * self.valve_map.0.get(&self.node).unwrap().0;
-                self.value += new_value;
-                self.time_left -= 1;
-                return true;
-            } else if self.goal.is_some() {
-                // Move towards goal if not there
-                if let Some(path) = shortest_path(&self.goal.unwrap(), &self.node, self.valve_map) {
-                    self.node = path[1];
-                } else {
-                    panic!("no path from node to goal");
-                }
-            }
-            self.time_left -= 1;
-        }
-        false
-    }
+/// With an elephant, who takes the other valves.
+fn part2(input: &[Input]) -> i64 {
+    most_pressure(input, 2, 26)
 }
 
-type Plan = (Vec<usize>, Vec<usize>);
-
-fn simulate_path2(
-    plan: &Plan,
-    mapping: &UniqueMapping,
-    valve_map: &ValveIntMap,
-    valves_enabled: &HashMap<usize, bool>,
-) -> i64 {
-    let mut p1 = Agent::new(26, mapping.word_to_int("AA"), valve_map);
-    let mut path1_iter = plan.0.iter().copied();
-    p1.goal = path1_iter.next();
-    p1.goal = path1_iter.next();
-
-    let mut p2 = Agent::new(26, mapping.word_to_int("AA"), valve_map);
-    let mut path2_iter = plan.1.iter().copied();
-    p2.goal = path2_iter.next();
-    p2.goal = path2_iter.next();
-
-    let mut temp_valves_enabled = valves_enabled.clone();
-
-    for t in 0..26 {
-        if p1.time_step(&mut temp_valves_enabled) {
-            p1.goal = path1_iter.next();
-        }
-        if p2.time_step(&mut temp_valves_enabled) {
-            p2.goal = path2_iter.next();
-        }
-    }
+pub struct Day16;
 
-    // Return total value
-    p1.value + p2.value
-}
+impl Solution for Day16 {
+    type Input = Vec<Input>;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-#[aoc(day16, part2)]
-fn part2(input: &[Input]) -> i64 {
-    let mapping = populate_word_mapping(input);
-    let mut valve_map = ValveIntMap::new();
-    for line in input {
-        valve_map.0.insert(
-            mapping.word_to_int(&line.0),
-            (
-                line.1,
-                line.2
-                    .iter()
-                    .map(|k| mapping.word_to_int(k))
-                    .collect::<Vec<_>>(),
-                false,
-            ),
-        );
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
     }
-    let rates: HashMap<usize, i64> = valve_map.clone().0.iter().map(|(&k, v)| (k, v.0)).collect();
-    let valves_enabled: HashMap<usize, bool> =
-        valve_map.clone().0.iter().map(|(&k, v)| (k, v.2)).collect();
-    let possible_nodes: HashSet<usize> = valve_map.0.keys().cloned().collect();
-    let path_lengths = create_path_lengths(&possible_nodes, &valve_map);
-    let good_nodes: HashSet<usize> = possible_nodes
-        .into_iter()
-        .filter(|n| valve_map.0.get(n).unwrap().0 > 0)
-        .collect();
-
-    // For one of us, choose some routes
-    let mut all_paths: HashMap<Plan, i64> = HashMap::new();
-    let combos_5: Vec<Vec<usize>> = good_nodes.iter().copied().combinations(5).collect();
-    println!("combos_5 done");
-    let c5_complement: Vec<Vec<usize>> = combos_5.iter().map(|c5| {
-        good_nodes.iter().copied().filter(|x| !c5.contains(x)).collect()
-    }).collect();
-    println!("combos_5 complement done");
-
-    let mut cntr = 0;
-    for (set, cset) in combos_5.iter().zip(c5_complement.iter()) {
-        println!("cntr: {}", cntr);
-        cntr += 1;
-        let mut paths1: Vec<Vec<usize>> = set.iter().copied().permutations(5).collect();
-        let mut paths2: Vec<Vec<usize>> = cset.iter().copied().permutations(5).collect();
-
-        paths1.insert(0, mapping.word_to_int("AA"));
-        paths2.insert(0, mapping.word_to_int("AA"));
-
-        for p1 in &paths1 {
-            for p2 in &paths2 {
-                let value = simulate_path2(&(p1.to_vec(), p2.to_vec()), &mapping, &valve_map, &valves_enabled);
-                all_paths.insert((p1.clone(), p2.clone()), value);
-            }
-        }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
     }
-    // The example inputs
-    /*
-    let path1 = vec!["AA", "JJ", "BB", "CC"].iter().map(|x| mapping.word_to_int(x)).collect();
-    let path2 = vec!["AA", "DD", "HH", "EE"].iter().map(|x| mapping.word_to_int(x)).collect();
-    let plan = (path1, path2);
-    let value = simulate_path2(&plan, &mapping, &valve_map, &valves_enabled);
-    println!("Test value: {}", value);
-    */
-
-    let all_paths: Vec<(Plan, i64)> = all_paths.into_iter().collect();
-    0
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/16.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(16, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 1651);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/16.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(16, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 1707);
     }
+
+    #[test]
+    fn test_many_valves() {
+        // Broken valves listed first, so the ones with a flow rate are
+        // numbered well past 64
+        let mut text = String::new();
+        for a in ['P', 'Q', 'R'] {
+            for b in 'A'..='Z' {
+                text += &format!(
+                    "Valve {}{} has flow rate=0; tunnels lead to valves AA\n",
+                    a, b
+                );
+            }
+        }
+        text += &load(16, &InputKind::Example(0)).unwrap();
+        let input = load_input(&text).unwrap();
+        assert_eq!(part1(&input), 1651);
+    }
+
+    #[test]
+    fn test_load_input() {
+        let valve = |name: &str, rate: i64, to: &str| {
+            format!(
+                "Valve {} has flow rate={}; tunnels lead to valves {}\n",
+                name, rate, to
+            )
+        };
+        let error = load_input(&(valve("AA", 0, "BB, CC") + &valve("BB", 1, "AA"))).unwrap_err();
+        assert_eq!((error.line, error.column), (1, 54));
+        assert_eq!(error.expected, "a valve listed in the input");
+
+        let error = load_input(&valve("BB", 1, "BB")).unwrap_err();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (2, "a line for valve `AA`")
+        );
+
+        let mut text = valve("AA", 0, "AA");
+        for i in 0..65 {
+            text += &valve(&format!("V{}", i), 1, "AA");
+        }
+        let error = load_input(&text).unwrap_err();
+        assert_eq!((error.line, error.column), (66, 25));
+    }
 }
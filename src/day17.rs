@@ -1,7 +1,9 @@
-use std::collections::HashSet;
-
-#[allow(unused_imports)]
-use std::io::stdin;
+use crate::cycle::Detector;
+use crate::geom::Point2;
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
+use crate::sparse::SparseGrid;
+use crate::viz::{Frame, Viz};
 
 type Input = char;
 
@@ -14,7 +16,9 @@ enum RockType {
     Square,
 }
 
-type Rock = (i64, i64, RockType);
+/// Upper left corner of a rock, and its shape. Board points are `x` across
+/// from the left wall and `y` up from the floor.
+type Rock = (Point2<i64>, RockType);
 
 // Edges relative to upper left corner of shape
 const ROCK_RIGHT_EDGE: [i64; 5] = [3, 2, 2, 0, 1];
@@ -22,22 +26,27 @@ const ROCK_BOTTOM_EDGE: [i64; 5] = [0, -2, -2, -3, -1];
 
 fn rock_types() -> Vec<Rock> {
     vec![
-        (0, 2, RockType::HLine),
-        (0, 2, RockType::Plus),
-        (0, 2, RockType::LFlipped),
-        (0, 2, RockType::VLine),
-        (0, 2, RockType::Square),
+        (Point2::new(2, 0), RockType::HLine),
+        (Point2::new(2, 0), RockType::Plus),
+        (Point2::new(2, 0), RockType::LFlipped),
+        (Point2::new(2, 0), RockType::VLine),
+        (Point2::new(2, 0), RockType::Square),
     ]
 }
 
-fn rock_shapes() -> Vec<Vec<(i64, i64)>> {
-    vec![
-        vec![(0, 0), (0, 1), (0, 2), (0, 3)],             // hline `-`
-        vec![(0, 1), (-1, 0), (-1, 1), (-1, 2), (-2, 1)], // plus `+`
-        vec![(0, 2), (-1, 2), (-2, 0), (-2, 1), (-2, 2)], // hflipped `L`
-        vec![(0, 0), (-1, 0), (-2, 0), (-3, 0)],          // vline `|`
-        vec![(0, 0), (0, 1), (-1, 0), (-1, 1)],           // box `#`
-    ]
+fn rock_shapes() -> Vec<Vec<Point2<i64>>> {
+    // (x, y) of each square, going down from the top
+    let shapes: [&[(i64, i64)]; 5] = [
+        &[(0, 0), (1, 0), (2, 0), (3, 0)],             // hline `-`
+        &[(1, 0), (0, -1), (1, -1), (2, -1), (1, -2)], // plus `+`
+        &[(2, 0), (2, -1), (0, -2), (1, -2), (2, -2)], // hflipped `L`
+        &[(0, 0), (0, -1), (0, -2), (0, -3)],          // vline `|`
+        &[(0, 0), (1, 0), (0, -1), (1, -1)],           // box `#`
+    ];
+    shapes
+        .iter()
+        .map(|shape| shape.iter().map(|&p| p.into()).collect())
+        .collect()
 }
 
 #[derive(Clone, Debug)]
@@ -65,21 +74,19 @@ impl Iterator for RockIter {
     }
 }
 
-struct InputIter {
+#[derive(Clone, Debug)]
+struct InputIter<'a> {
     idx: usize,
-    input: Vec<char>,
+    input: &'a [char],
 }
 
-impl InputIter {
-    fn new(input: &[char]) -> Self {
-        Self {
-            idx: 0,
-            input: input.to_vec(),
-        }
+impl<'a> InputIter<'a> {
+    fn new(input: &'a [char]) -> Self {
+        Self { idx: 0, input }
     }
 }
 
-impl Iterator for InputIter {
+impl Iterator for InputIter<'_> {
     type Item = char;
     fn next(&mut self) -> Option<Self::Item> {
         let output = Some(self.input[self.idx]);
@@ -89,278 +96,280 @@ impl Iterator for InputIter {
     }
 }
 
-#[aoc_generator(day17)]
-fn load_input(input: &str) -> Vec<Input> {
-    input.trim().chars().collect()
+fn load_input(input: &str) -> Result<Vec<Input>, ParseError> {
+    let mut output = vec![];
+    let mut lines = Lines::new(17, input);
+    for line in lines.by_ref() {
+        for (col, c) in line.text.trim_end().chars().enumerate() {
+            match c {
+                '<' | '>' => output.push(c),
+                _ => return Err(line.error_at(col + 1, "`<` or `>`")),
+            }
+        }
+    }
+    if output.is_empty() {
+        return Err(lines.next_or("the jet pattern").unwrap_err());
+    }
+    Ok(output)
+}
+
+/// Whether `rock` moved by `shift` would hit anything already on the board.
+fn blocked(
+    rock: Rock,
+    shift: Point2<i64>,
+    rock_shapes: &[Vec<Point2<i64>>],
+    board: &SparseGrid,
+) -> bool {
+    rock_shapes[rock.1 as usize]
+        .iter()
+        .any(|&pt| board.contains(pt + rock.0 + shift))
 }
 
 fn wind_move_rock(
     rock: Rock,
     wind: char,
-    rock_shapes: &[Vec<(i64, i64)>],
-    board: &HashSet<(i64, i64)>,
+    rock_shapes: &[Vec<Point2<i64>>],
+    board: &SparseGrid,
 ) -> Rock {
-    match wind {
-        '<' => {
-            if rock.1 > 0 {
-                // Edge not blocking movement, what about board?
-                let shape = &rock_shapes[rock.2 as usize];
-                let mut blocked = false;
-                for pt in shape {
-                    let next_pos = (pt.0 + rock.0, pt.1 + rock.1 - 1);
-                    if board.contains(&next_pos) {
-                        blocked = true;
-                        break;
-                    }
-                }
-                if !blocked {
-                    (rock.0, rock.1 - 1, rock.2)
-                } else {
-                    rock
-                }
-            } else {
-                rock
-            }
-        }
-        '>' => {
-            let right = ROCK_RIGHT_EDGE[rock.2 as usize];
-            if rock.1 + right < 6 {
-                // Edge not blocking movement, what about board?
-                let shape = &rock_shapes[rock.2 as usize];
-                let mut blocked = false;
-                for pt in shape {
-                    let next_pos = (pt.0 + rock.0, pt.1 + rock.1 + 1);
-                    if board.contains(&next_pos) {
-                        blocked = true;
-                        break;
-                    }
-                }
-                if !blocked {
-                    (rock.0, rock.1 + 1, rock.2)
-                } else {
-                    rock
-                }
-            } else {
-                rock
-            }
-        }
+    let (shift, in_bounds) = match wind {
+        '<' => (Point2::new(-1, 0), rock.0.x > 0),
+        '>' => (
+            Point2::new(1, 0),
+            rock.0.x + ROCK_RIGHT_EDGE[rock.1 as usize] < 6,
+        ),
         _ => panic!("Invalid wind direction"),
+    };
+    if in_bounds && !blocked(rock, shift, rock_shapes, board) {
+        (rock.0 + shift, rock.1)
+    } else {
+        rock
     }
 }
 
 fn move_down_rock(
     rock: Rock,
-    rock_shapes: &[Vec<(i64, i64)>],
-    board: &HashSet<(i64, i64)>,
+    rock_shapes: &[Vec<Point2<i64>>],
+    board: &SparseGrid,
 ) -> (Rock, bool) {
-    if rock.0 > 0 {
-        // Floor not blocking movement, what about board?
-        let shape = &rock_shapes[rock.2 as usize];
-        let mut blocked = false;
-        for pt in shape {
-            let next_pos = (pt.0 + rock.0 - 1, pt.1 + rock.1);
-            if board.contains(&next_pos) {
-                blocked = true;
-                break;
-            }
-        }
-        if !blocked {
-            ((rock.0 - 1, rock.1, rock.2), false)
-        } else {
-            (rock, true)
-        }
+    let shift = Point2::new(0, -1);
+    if rock.0.y > 0 && !blocked(rock, shift, rock_shapes, board) {
+        ((rock.0 + shift, rock.1), false)
     } else {
         (rock, true)
     }
 }
 
-#[allow(dead_code)]
-fn draw_board(board: &HashSet<(i64, i64)>, offset: i64) {
+fn tower_height(board: &SparseGrid) -> i64 {
+    board.bounds().map_or(0, |b| b.max.y)
+}
+
+fn draw_board(board: &SparseGrid, offset: i64) -> Frame {
+    let mut frame = Frame::new(9, 51, '.');
     for h in 0..50 {
-        print!("|");
+        frame.put(0, h, '|');
         for w in 0..7 {
-            if board.contains(&(offset + 50 - h, w)) {
-                print!("#");
-            } else {
-                print!(".");
+            if board.contains(Point2::new(w, offset + 50 - h as i64)) {
+                frame.put(w as usize + 1, h, '#');
             }
         }
-        print!("|");
-        println!();
+        frame.put(8, h, '|');
+    }
+    for (w, c) in "+-------+".chars().enumerate() {
+        frame.put(w, 50, c);
     }
-    println!("+-------+");
+    frame
 }
 
 fn rock_iter(
     riter: &mut RockIter,
     input_iter: &mut InputIter,
-    board: &mut HashSet<(i64, i64)>,
-    rshapes: &[Vec<(i64, i64)>],
-) {
+    board: &mut SparseGrid,
+    rshapes: &[Vec<Point2<i64>>],
+) -> Rock {
     let mut next_rock = riter.next().unwrap();
 
     // Set the height to 3 above tallest point in board
-    let highest_point = board.iter().map(|p| p.0).max().unwrap_or(0);
-    next_rock.0 += highest_point + 4 - ROCK_BOTTOM_EDGE[next_rock.2 as usize];
+    let highest_point = tower_height(board);
+    next_rock.0.y += highest_point + 4 - ROCK_BOTTOM_EDGE[next_rock.1 as usize];
 
     loop {
         let wind = input_iter.next().unwrap();
-        //println!("\nwind: {}", wind);
-        //println!("rock: {:?}", next_rock);
-
-        //draw_board(&board, 0);
-        //let mut asdf = String::new();
-        //stdin().read_line(&mut asdf);
-
         let shifted_rock = wind_move_rock(next_rock, wind, rshapes, board);
         let (fallen_rock, done_falling) = move_down_rock(shifted_rock, rshapes, board);
         if done_falling {
-            for point in &rshapes[next_rock.2 as usize] {
-                let shifted_point = (point.0 + fallen_rock.0, point.1 + fallen_rock.1);
-                board.insert(shifted_point);
+            for &point in &rshapes[next_rock.1 as usize] {
+                board.insert(point + fallen_rock.0, ());
             }
-            break;
+            return fallen_rock;
         } else {
             next_rock = fallen_rock;
         }
     }
 }
 
-fn solve(input: &[Input], nrocks: usize) -> i64 {
+fn solve(input: &[Input], nrocks: usize, viz: &mut Viz) -> i64 {
     let mut input_iter = InputIter::new(input);
     let rshapes = rock_shapes();
     let mut riter = RockIter::new();
-    let mut board: HashSet<(i64, i64)> = HashSet::new();
+    let mut board = SparseGrid::new();
     for x in 0..7 {
-        board.insert((0, x));
+        board.insert(Point2::new(x, 0), ());
     }
-    for _ in 0..nrocks {
+    for n in 0..nrocks {
         rock_iter(&mut riter, &mut input_iter, &mut board, &rshapes);
+        if viz.is_on() {
+            let height = tower_height(&board);
+            viz.show(|| {
+                draw_board(&board, (height - 45).max(0)).caption(format!("Rock {}", n + 1))
+            });
+            if !viz.tick(&[("rock", n as i64 + 1), ("height", height)]) {
+                break;
+            }
+        }
     }
 
-    board.iter().map(|p| p.0).max().unwrap_or(0)
+    tower_height(&board)
 }
 
-#[aoc(day17, part1)]
 fn part1(input: &[Input]) -> i64 {
-    solve(input, 2022)
+    solve(input, 2022, &mut Viz::off())
 }
 
-fn find_cycle_height(board: &HashSet<(i64, i64)>, offset: i64, window: i64) -> i64 {
-    let height = board.iter().map(|p| p.0).max().unwrap_or(0);
-
-    // Save the first 40 lines
-    let mut pattern: HashSet<(i64, i64)> = HashSet::new();
-    for i in 0..window {
-        for x in 0..7 {
-            if board.contains(&(i + offset, x)) {
-                pattern.insert((i, x));
-            }
-        }
-    }
-
-    let mut i = offset + 1;
+/// How far below the top of the tower the cycle key looks at first. A
+/// column the wind never lets a rock into stays open all the way down, so
+/// the key has to stop somewhere for the tower to ever repeat.
+const DEPTH: i64 = 64;
+
+/// The tower height after `rocks` rocks, found by looking for a repeat in
+/// the next rock, the next gust and the top `depth` rows of the tower. A rock
+/// that comes to rest clear of the cut only ever touched rows the key
+/// covers, so a period where every rock did is a real one. Otherwise gives
+/// up with the depth the deepest rock in the period needed.
+fn cycle_within(input: &[Input], rocks: u64, depth: i64) -> Result<i64, i64> {
+    let rshapes = rock_shapes();
+    let mut riter = RockIter::new();
+    let mut winds = InputIter::new(input);
+    let mut board: SparseGrid = (0..7).map(|x| Point2::new(x, 0)).collect();
+    // Each row of the tower as a bitmask, to take keys from cheaply
+    let mut rows = vec![0b111_1111u8];
+    let mut seen = Detector::new();
+    let mut heights = vec![0];
+    // Where each rock came to rest, relative to the top before it fell
+    let mut landed = vec![];
     loop {
-        if i > height {
-            panic!("find_cycle_height failed");
+        let height = tower_height(&board);
+        let cut = (height - depth + 1).max(0) as usize;
+        let key = (riter.idx, winds.idx, rows[cut..].to_vec());
+        if let Some(cycle) = seen.observe(key) {
+            let deepest = landed[cycle.start..].iter().min().unwrap();
+            if *deepest > 1 - depth {
+                return Ok(cycle.extrapolate(&heights, rocks));
+            }
+            return Err(2 - deepest);
         }
-        let mut chunk: HashSet<(i64, i64)> = HashSet::new();
-        for j in 0..window {
-            for x in 0..7 {
-                if board.contains(&(i + j, x)) {
-                    chunk.insert((j, x));
-                }
+        let rock = rock_iter(&mut riter, &mut winds, &mut board, &rshapes);
+        for &point in &rshapes[rock.1 as usize] {
+            let p = point + rock.0;
+            if rows.len() <= p.y as usize {
+                rows.resize(p.y as usize + 1, 0);
             }
+            rows[p.y as usize] |= 1 << p.x;
         }
+        landed.push(rock.0.y + ROCK_BOTTOM_EDGE[rock.1 as usize] - height);
+        heights.push(tower_height(&board));
+    }
+}
 
-        if pattern.is_subset(&chunk) {
-            return i - offset;
-        } else {
-            i += 1;
+/// Once the chamber comes back round to a state it has been in, the tower
+/// grows by the same amount every period, so the height can be carried
+/// forward from there. When a rock in the period fell past the cut, the
+/// key missed something that decided where it went, so look again deeper.
+fn tower_after(input: &[Input], rocks: u64) -> i64 {
+    let mut depth = DEPTH;
+    loop {
+        match cycle_within(input, rocks, depth) {
+            Ok(height) => return height,
+            Err(needed) => depth = needed.max(depth * 2),
         }
     }
 }
 
-/// NOTE: This works because we have 5 shapes that repeat, and a length 40
-/// input that repeats, meaning the inputs just cycle every 40 blocks. IF
-/// nothing slips through the gaps then it's clean.
-#[aoc(day17, part2)]
 fn part2(input: &[Input]) -> i64 {
-    let mut input_iter = InputIter::new(input);
-    let rshapes = rock_shapes();
-    let mut riter = RockIter::new();
-    let mut board: HashSet<(i64, i64)> = HashSet::new();
-    for x in 0..7 {
-        board.insert((0, x));
-    }
-
-    for _ in 0..4000 {
-        rock_iter(&mut riter, &mut input_iter, &mut board, &rshapes);
-    }
+    tower_after(input, 1_000_000_000_000)
+}
 
-    let height = board.iter().map(|p| p.0).max().unwrap_or(0);
+pub struct Day17;
 
-    let offset = 500;
-    let window = 40;
-    let repeat_height = find_cycle_height(&board, offset, window);
+impl Solution for Day17 {
+    type Input = Vec<Input>;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-    // Save a pattern
-    let mut pattern: HashSet<(i64, i64)> = HashSet::new();
-    for i in 0..window {
-        for x in 0..7 {
-            if board.contains(&(height - i, x)) {
-                pattern.insert((i, x));
-            }
-        }
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
     }
 
-    let repeat_time;
-    let mut cntr = 1;
-    loop {
-        rock_iter(&mut riter, &mut input_iter, &mut board, &rshapes);
-        let height = board.iter().map(|p| p.0).max().unwrap_or(0);
-
-        let mut chunk: HashSet<(i64, i64)> = HashSet::new();
-        for i in 0..window {
-            for x in 0..7 {
-                if board.contains(&(height - i, x)) {
-                    chunk.insert((i, x));
-                }
-            }
-        }
-
-        if pattern.is_subset(&chunk) {
-            repeat_time = cntr;
-            break;
-        } else {
-            cntr += 1;
-        }
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
     }
 
-    let next_sim: i64 = 1000000000000 % repeat_time;
-    let ncycles: i64 = 1000000000000 / repeat_time;
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
 
-    let mut height_so_far = repeat_height * ncycles;
-    height_so_far += solve(input, next_sim as usize);
-    height_so_far
+    fn visualize(input: &Self::Input, viz: &mut Viz) -> bool {
+        solve(input, 2022, viz);
+        true
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/17.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(17, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 3068);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/17.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(17, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 1514285714288);
     }
+
+    #[test]
+    fn test_open_column() {
+        // The wind keeps rocks away from the right wall, leaving it open
+        // down to the floor
+        for jets in ["<<>", "<<>><<<<<<"] {
+            let input = load_input(jets).unwrap();
+            assert_eq!(tower_after(&input, 2022), part1(&input));
+            assert!(part2(&input) > 0);
+        }
+    }
+
+    #[test]
+    fn test_deep_well() {
+        // Enough jets one way for rocks to come to rest far down a well the
+        // first cut doesn't reach
+        for (left, right) in [(400, 100), (4000, 1000)] {
+            let jets = "<".repeat(left) + &">".repeat(right);
+            let input = load_input(&jets).unwrap();
+            let expected = solve(&input, 2022, &mut Viz::off());
+            assert_eq!(tower_after(&input, 2022), expected);
+        }
+    }
+
+    #[test]
+    fn test_load_input() {
+        assert_eq!(load_input("<>\n").unwrap(), vec!['<', '>']);
+        assert_eq!(load_input("").unwrap_err().expected, "the jet pattern");
+        assert_eq!(load_input("\n").unwrap_err().expected, "the jet pattern");
+        assert_eq!(load_input("<^>").unwrap_err().column, 2);
+    }
 }
@@ -1,17 +1,19 @@
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
 use rayon::prelude::*;
 use scan_fmt::scan_fmt;
 use std::collections::HashSet;
 
 type Input = (i64, i64, i64);
 
-#[aoc_generator(day18)]
-fn load_input(input: &str) -> Vec<Input> {
+fn load_input(input: &str) -> Result<Vec<Input>, ParseError> {
     let mut output = vec![];
-    for line in input.lines() {
-        let pt = scan_fmt!(line, "{},{},{}", i64, i64, i64).unwrap();
+    for line in Lines::new(18, input) {
+        let pt = scan_fmt!(line.text, "{},{},{}", i64, i64, i64)
+            .map_err(|_| line.scan_error("{},{},{}"))?;
         output.push(pt);
     }
-    output
+    Ok(output)
 }
 
 fn get_neighbors(cube: (i64, i64, i64)) -> Vec<(i64, i64, i64)> {
@@ -51,7 +53,6 @@ fn get_neighbors_on_graph(cube: (i64, i64, i64), graph: &HashSet<Input>) -> Vec<
     neighbors
 }
 
-#[aoc(day18, part1)]
 fn part1(input: &[Input]) -> usize {
     let mut nsides = input.len() * 6;
     let cubes: HashSet<Input> = input.iter().copied().collect();
@@ -139,27 +140,46 @@ fn _part2(input: &[Input], bound: i64) -> usize {
     nsides
 }
 
-#[aoc(day18, part2)]
 fn part2(input: &[Input]) -> usize {
     _part2(input, 23)
 }
 
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<Input>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/18.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(18, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 64);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/18.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(18, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(_part2(&input, 8), 58);
     }
 }
@@ -1,11 +1,13 @@
-use aoc_helpers::graph::Graph;
+use crate::optimize::{maximize, maximize_until, Problem};
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
+use crate::viz::Viz;
+use log::debug;
 use rayon::prelude::*;
 use scan_fmt::scan_fmt;
-use std::collections::{HashMap, HashSet};
-use std::io::stdin;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct Blueprint {
+pub struct Blueprint {
     idnum: i64,
     orebot_cost: i64,        // ore
     claybot_cost: i64,       // ore
@@ -27,252 +29,214 @@ impl Blueprint {
 
 type Input = Blueprint;
 
-#[aoc_generator(day19)]
-fn load_input(input: &str) -> Vec<Input> {
+const BLUEPRINT_FMT: &str = "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.";
+
+fn load_input(input: &str) -> Result<Vec<Input>, ParseError> {
     let mut output = vec![];
-    for line in input.lines() {
+    for line in Lines::new(19, input) {
         // (bnum, orebot, claybot, obsbot_ore, obsbot_clay, geobot_ore, geobot_obs)
-        let temp = scan_fmt!(line, "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.", i64, i64, i64, i64, i64, i64, i64).unwrap();
+        let temp = scan_fmt!(line.text, "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.", i64, i64, i64, i64, i64, i64, i64)
+            .map_err(|_| line.scan_error(BLUEPRINT_FMT))?;
         output.push(Blueprint::new(temp));
     }
-    output
+    Ok(output)
 }
 
+/// Robots and materials are counted in the order ore, clay, obsidian, geode.
+const GEODE: usize = 3;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 struct State {
-    ore_bots: i64,
-    clay_bots: i64,
-    obs_bots: i64,
-    geo_bots: i64,
-    ore: i64,
-    clay: i64,
-    obs: i64,
-    geo: i64,
+    time_left: i64,
+    bots: [i64; 4],
+    stock: [i64; 4],
 }
 
 impl State {
-    fn new() -> Self {
+    fn new(time_left: i64) -> Self {
         Self {
-            ore_bots: 1,
-            clay_bots: 0,
-            obs_bots: 0,
-            geo_bots: 0,
-            ore: 0,
-            clay: 0,
-            obs: 0,
-            geo: 0,
+            time_left,
+            bots: [1, 0, 0, 0],
+            stock: [0; 4],
         }
     }
 }
 
-// 0 - orebot
-// 1 - claybot
-// 2 - obsbot
-// 3 - geobot
-fn time_step(new_bot: Option<i64>, state: &mut State, blueprint: Blueprint) {
-    // Spend resources on new bots
-    match new_bot {
-        Some(0) => {
-            state.ore -= blueprint.orebot_cost;
-        }
-        Some(1) => {
-            state.ore -= blueprint.claybot_cost;
-        }
-        Some(2) => {
-            state.ore -= blueprint.obsbot_cost.0;
-            state.clay -= blueprint.obsbot_cost.1;
-        }
-        Some(3) => {
-            state.ore -= blueprint.geobot_cost.0;
-            state.obs -= blueprint.geobot_cost.1;
+/// The most geodes one blueprint can open in the time given. Each decision
+/// is which robot to build next, waiting for the materials if need be.
+struct Factory {
+    /// What each robot costs, in each material.
+    costs: [[i64; 4]; 4],
+    /// No point having more robots of a material than can be spent in a
+    /// minute, as only one robot is built at a time.
+    max_bots: [i64; 4],
+}
+
+impl Factory {
+    fn new(blueprint: &Blueprint) -> Self {
+        let costs = [
+            [blueprint.orebot_cost, 0, 0, 0],
+            [blueprint.claybot_cost, 0, 0, 0],
+            [blueprint.obsbot_cost.0, blueprint.obsbot_cost.1, 0, 0],
+            [blueprint.geobot_cost.0, 0, blueprint.geobot_cost.1, 0],
+        ];
+        let mut max_bots = [0, 0, 0, i64::MAX];
+        for cost in &costs {
+            for m in 0..GEODE {
+                max_bots[m] = max_bots[m].max(cost[m]);
+            }
         }
-        _ => (),
+        Self { costs, max_bots }
     }
 
-    // Bots collect resources
-    state.ore += state.ore_bots;
-    state.clay += state.clay_bots;
-    state.obs += state.obs_bots;
-    state.geo += state.geo_bots;
-
-    // Bots are built
-    match new_bot {
-        Some(0) => state.ore_bots += 1,
-        Some(1) => state.clay_bots += 1,
-        Some(2) => state.obs_bots += 1,
-        Some(3) => state.geo_bots += 1,
-        _ => (),
+    /// Minutes until robot `bot` can be paid for, or None if nothing makes
+    /// one of its materials yet.
+    fn wait_for(&self, state: &State, bot: usize) -> Option<i64> {
+        let mut wait = 0;
+        for m in 0..GEODE {
+            let short = self.costs[bot][m] - state.stock[m];
+            if short > 0 {
+                if state.bots[m] == 0 {
+                    return None;
+                }
+                wait = wait.max((short + state.bots[m] - 1) / state.bots[m]);
+            }
+        }
+        Some(wait)
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct DState {
-    time: i64,
-    money: (i64, i64, i64, i64),
-    nbots: (i64, i64, i64, i64),
-}
+impl Problem for Factory {
+    type State = State;
+    type Key = (i64, [i64; 3], [i64; 3]);
 
-impl DState {
-    fn new(time: i64, state: State) -> Self {
-        Self {
-            time,
-            money: (state.ore, state.clay, state.obs, state.geo),
-            nbots: (
-                state.ore_bots,
-                state.clay_bots,
-                state.obs_bots,
-                state.geo_bots,
-            ),
+    fn branch(&self, state: &State) -> Vec<State> {
+        let mut next = vec![];
+        for bot in 0..4 {
+            if state.bots[bot] >= self.max_bots[bot] {
+                continue;
+            }
+            let Some(wait) = self.wait_for(state, bot) else {
+                continue;
+            };
+            // A robot finished in the last minute never gets to work
+            let time_left = state.time_left - wait - 1;
+            if time_left <= 0 {
+                continue;
+            }
+            let mut built = *state;
+            built.time_left = time_left;
+            for m in 0..4 {
+                built.stock[m] += state.bots[m] * (wait + 1) - self.costs[bot][m];
+            }
+            built.bots[bot] += 1;
+            next.push(built);
         }
+        next
     }
 
-    fn to_state(&self) -> State {
-        let mut s = State::new();
-        s.ore_bots = self.nbots.0;
-        s.clay_bots = self.nbots.1;
-        s.obs_bots = self.nbots.2;
-        s.geo_bots = self.nbots.3;
-        s.ore = self.money.0;
-        s.clay = self.money.1;
-        s.obs = self.money.2;
-        s.geo = self.money.3;
-        s
+    /// Geodes opened by the end if no more robots are built.
+    fn value(&self, state: &State) -> i64 {
+        state.stock[GEODE] + state.bots[GEODE] * state.time_left
     }
-}
-
-fn metric(dstate: DState, blueprint: Blueprint) -> i64 {
-   dstate.nbots.3 + 10 * dstate.money.3
-}
-
-// 851 too low
-// 1032 too low
-// 1114
-// 1117
-// 1121 too low
-#[aoc(day19, part1)]
-fn part1(input: &[Input]) -> usize {
-    let mut answers = vec![];
-    for (bnum, blueprint) in input.iter().enumerate() {
-        let obs_time = blueprint.geobot_cost.1 + 1;
-        let mut state = State::new();
-        let mut explored_states = Vec::with_capacity(1_000_000);
-        let mut dstates_to_investigate = Vec::with_capacity(1_000_000);
-        dstates_to_investigate.push(DState::new(24, state));
-        let mut asdf = String::new();
-        let mut cntr = 0;
 
-        let mut max_ore = vec![
-            blueprint.orebot_cost,
-            blueprint.claybot_cost,
-            blueprint.obsbot_cost.0,
-            blueprint.geobot_cost.0,
-        ];
-        let max_ore = max_ore.into_iter().max().unwrap();
-        let max_clay = blueprint.obsbot_cost.1;
-        let max_obs = blueprint.geobot_cost.1;
-        loop {
-            /*
-            stdin().read_line(&mut asdf);
-            println!();
-            println!("# explored_states: {}", explored_states.len());
-            println!("# dstates_to_investigate: {}", dstates_to_investigate.len());
-            */
+    /// As if a geode robot could be built every minute from now on.
+    fn bound(&self, state: &State) -> i64 {
+        let t = state.time_left;
+        self.value(state) + t * (t - 1) / 2
+    }
 
-            let dstate = dstates_to_investigate.pop().unwrap(); // try the next highest priority state
-                                                                //println!("  {:?}", dstate);
+    /// Geodes already counted in the value don't change what happens next.
+    fn key(&self, state: &State) -> Self::Key {
+        let [a, b, c, _] = state.bots;
+        let [x, y, z, _] = state.stock;
+        (state.time_left, [a, b, c], [x, y, z])
+    }
+}
 
-            // If time remaining, explore frontier of dstate
-            let mut next_dstates = vec![];
-            if dstate.time > 0 {
-                let state = dstate.to_state();
+/// Most geodes each blueprint can open in `minutes`, stepping through the
+/// search for each. None if the stepper quit.
+fn max_geodes(input: &[Input], minutes: i64, viz: &mut Viz) -> Option<Vec<i64>> {
+    let mut output = vec![];
+    for blueprint in input {
+        let factory = Factory::new(blueprint);
+        let best = maximize_until(&factory, State::new(minutes), |stats| {
+            viz.tick(&[
+                ("blueprint", blueprint.idnum),
+                ("expanded", stats.expanded as i64),
+                ("pruned", (stats.bounded + stats.dominated) as i64),
+            ])
+        })?;
+        debug!(
+            "Blueprint {}: {} geodes, {:?}",
+            blueprint.idnum, best.value, best.stats
+        );
+        output.push(best.value);
+    }
+    Some(output)
+}
 
-                // Calculate the options from this `State`
-                // Pre pruning trick: Don't build more of a bot than any other bot takes to build
-                // as input.
-                let mut options = vec![None];
-                if state.ore >= blueprint.orebot_cost && state.ore_bots < max_ore {
-                    // Orebot can be built
-                    options.push(Some(0));
-                }
-                if state.ore >= blueprint.claybot_cost && state.clay_bots < max_clay {
-                    // Claybot can be built
-                    options.push(Some(1));
-                }
-                if state.ore >= blueprint.obsbot_cost.0
-                    && state.clay >= blueprint.obsbot_cost.1
-                    && state.obs_bots < max_obs
-                {
-                    // Obsbot can be built
-                    options.push(Some(2));
-                }
-                if state.ore >= blueprint.geobot_cost.0 && state.obs >= blueprint.geobot_cost.1 {
-                    // Geobot can be built
-                    options.push(Some(3));
-                }
+fn part1(input: &[Input]) -> i64 {
+    let geodes: Vec<i64> = input
+        .par_iter()
+        .map(|blueprint| maximize(&Factory::new(blueprint), State::new(24)).value)
+        .collect();
+    input
+        .iter()
+        .zip(geodes)
+        .map(|(blueprint, geodes)| blueprint.idnum * geodes)
+        .sum()
+}
 
-                // Find possible next DStates
-                for choice in &options {
-                    let mut temp_state = state;
-                    time_step(*choice, &mut temp_state, *blueprint);
-                    next_dstates.push(DState::new(dstate.time - 1, temp_state));
-                }
-            }
+fn part2(input: &[Input]) -> i64 {
+    input
+        .par_iter()
+        .take(3)
+        .map(|blueprint| maximize(&Factory::new(blueprint), State::new(32)).value)
+        .product()
+}
 
-            // Keep track of explored states
-            explored_states.push(dstate);
+pub struct Day19;
 
-            // Some heuristics
-            // If less than 1 obsbot with 5 or less steps left, probably not it.
-            dstates_to_investigate.append(&mut next_dstates);
+impl Solution for Day19 {
+    type Input = Vec<Input>;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-            // Count terminal nodes
-            if cntr % 100_000 == 0 {
-                dstates_to_investigate
-                    .sort_by(|a, b| metric(*a, *blueprint).cmp(&metric(*b, *blueprint)));
-                let n_terminal_nodes = explored_states.iter().filter(|x| x.time == 0).count();
-                println!("# Terminal States: {}", n_terminal_nodes);
-                println!("# Explored States: {}", explored_states.len());
-                if n_terminal_nodes > 1_000_000 {
-                    break;
-                }
-            }
-            cntr += 1;
-        }
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
 
-        let mut terminal_states: Vec<DState> = explored_states
-            .into_iter()
-            .filter(|x| x.time == 0)
-            .collect();
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
 
-        terminal_states.sort_by(|a, b| a.money.3.cmp(&b.money.3));
-        let max_geodes = terminal_states.last().unwrap().money.3;
-        println!("# Geodes: {}", max_geodes);
-        answers.push((bnum + 1) * max_geodes as usize);
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
     }
-    answers.iter().sum::<usize>()
-}
 
-#[aoc(day19, part2)]
-fn part2(input: &[Input]) -> i64 {
-    0
+    fn visualize(input: &Self::Input, viz: &mut Viz) -> bool {
+        max_geodes(input, 24, viz).is_some()
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/19.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(19, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
+        assert_eq!(max_geodes(&input, 24, &mut Viz::off()), Some(vec![9, 12]));
         assert_eq!(part1(&input), 33);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/19.txt").unwrap();
-        let input = load_input(&input);
-        assert_eq!(part2(&input), 0);
+        let input = load(19, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
+        assert_eq!(part2(&input), 56 * 62);
     }
 }
@@ -1,70 +1,468 @@
-use scan_fmt::scan_fmt;
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
+use itertools::Itertools;
+use std::cmp::Reverse;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Shape {
+    Rock,
+    Paper,
+    Scissors,
+    Lizard,
+    Spock,
+}
+
+/// How a round ends for us.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    /// In the order the guide's X, Y and Z stand for them in part 2.
+    pub const ALL: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+}
+
+/// A game of Rock Paper Scissors or one of its variants: which shapes are
+/// in play, what beats what, and how rounds are scored.
+pub trait RuleSet {
+    /// The shapes in play. The opponent's letters A, B, C, ... stand for
+    /// them in this order, as do our letters when read as shapes.
+    fn shapes(&self) -> &[Shape];
+
+    /// The letters our column may use, one per shape.
+    fn codes(&self) -> &str;
+
+    fn beats(&self, a: Shape, b: Shape) -> bool;
+
+    /// Points for the shape we play: 1 for the first, 2 for the second, ...
+    fn shape_score(&self, shape: Shape) -> u64 {
+        self.shapes().iter().position(|&s| s == shape).unwrap() as u64 + 1
+    }
+
+    fn outcome_score(&self, outcome: Outcome) -> u64 {
+        match outcome {
+            Outcome::Lose => 0,
+            Outcome::Draw => 3,
+            Outcome::Win => 6,
+        }
+    }
+
+    fn outcome(&self, us: Shape, them: Shape) -> Outcome {
+        if self.beats(us, them) {
+            Outcome::Win
+        } else if self.beats(them, us) {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    fn score(&self, us: Shape, them: Shape) -> u64 {
+        self.shape_score(us) + self.outcome_score(self.outcome(us, them))
+    }
+
+    /// The shape to play against `them` for `outcome`, the first in order
+    /// if there's more than one.
+    fn respond(&self, them: Shape, outcome: Outcome) -> Shape {
+        *self
+            .shapes()
+            .iter()
+            .find(|&&us| self.outcome(us, them) == outcome)
+            .expect("every outcome is possible against every shape")
+    }
+}
+
+/// Rock, Paper, Scissors.
+pub struct Classic;
+
+impl RuleSet for Classic {
+    fn shapes(&self) -> &[Shape] {
+        &[Shape::Rock, Shape::Paper, Shape::Scissors]
+    }
+
+    fn codes(&self) -> &str {
+        "XYZ"
+    }
+
+    fn beats(&self, a: Shape, b: Shape) -> bool {
+        matches!(
+            (a, b),
+            (Shape::Rock, Shape::Scissors)
+                | (Shape::Paper, Shape::Rock)
+                | (Shape::Scissors, Shape::Paper)
+        )
+    }
+}
+
+/// Rock, Paper, Scissors, Lizard, Spock, where every shape beats two others.
+/// Our column runs V to Z.
+pub struct LizardSpock;
+
+impl RuleSet for LizardSpock {
+    fn shapes(&self) -> &[Shape] {
+        &[
+            Shape::Rock,
+            Shape::Paper,
+            Shape::Scissors,
+            Shape::Lizard,
+            Shape::Spock,
+        ]
+    }
+
+    fn codes(&self) -> &str {
+        "VWXYZ"
+    }
+
+    fn beats(&self, a: Shape, b: Shape) -> bool {
+        use Shape::*;
+        matches!(
+            (a, b),
+            (Scissors, Paper)
+                | (Paper, Rock)
+                | (Rock, Lizard)
+                | (Lizard, Spock)
+                | (Spock, Scissors)
+                | (Scissors, Lizard)
+                | (Lizard, Paper)
+                | (Paper, Spock)
+                | (Spock, Rock)
+                | (Rock, Scissors)
+        )
+    }
+}
+
+/// Another rule set's game with its own points for each shape, in the
+/// order of `shapes`, and for losing, drawing and winning.
+pub struct Scored<R> {
+    pub rules: R,
+    pub shape_points: Vec<u64>,
+    pub outcome_points: [u64; 3],
+}
+
+impl<R: RuleSet> RuleSet for Scored<R> {
+    fn shapes(&self) -> &[Shape] {
+        self.rules.shapes()
+    }
+
+    fn codes(&self) -> &str {
+        self.rules.codes()
+    }
+
+    fn beats(&self, a: Shape, b: Shape) -> bool {
+        self.rules.beats(a, b)
+    }
+
+    fn shape_score(&self, shape: Shape) -> u64 {
+        let i = self.shapes().iter().position(|&s| s == shape).unwrap();
+        self.shape_points[i]
+    }
+
+    fn outcome_score(&self, outcome: Outcome) -> u64 {
+        self.outcome_points[outcome as usize]
+    }
+}
+
+/// One line of the strategy guide. What our letter means is the puzzle, so
+/// it's kept as its position in the rule set's `codes`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub them: Shape,
+    pub code: usize,
+}
+
+/// Parse a guide for the game `rules`, rejecting letters it doesn't use.
+pub fn load_with(input: &str, rules: &impl RuleSet) -> Result<Vec<Round>, ParseError> {
+    let shapes = rules.shapes();
+    let theirs: String = (b'A'..).take(shapes.len()).map(char::from).collect();
+    let ours = rules.codes();
+    let letters = |set: &str| {
+        let set: Vec<_> = set.chars().map(String::from).collect();
+        format!(
+            "{} or {}",
+            set[..set.len() - 1].join(", "),
+            set[set.len() - 1]
+        )
+    };
 
-#[aoc_generator(day2)]
-pub fn load_input(input: &str) -> Vec<(char, char)> {
     let mut output = vec![];
-    for line in input.lines() {
-        output.push(scan_fmt!(line, "{} {}", char, char).unwrap());
-    }
-    output
-}
-
-#[aoc(day2, part1)]
-pub fn part1(input: &[(char, char)]) -> u64 {
-    let mut score = 0;
-    for ll in input {
-        score += match ll {
-            ('A', 'X') => 1 + 3,
-            ('A', 'Y') => 2 + 6,
-            ('A', 'Z') => 3,
-            ('B', 'X') => 1,
-            ('B', 'Y') => 2 + 3,
-            ('B', 'Z') => 3 + 6,
-            ('C', 'X') => 1 + 6,
-            ('C', 'Y') => 2,
-            ('C', 'Z') => 3 + 3,
-            _ => 0,
-        };
+    for line in Lines::new(2, input) {
+        let chars: Vec<char> = line.text.chars().collect();
+        let them = theirs
+            .chars()
+            .position(|c| Some(&c) == chars.first())
+            .ok_or_else(|| line.error_at(1, &letters(&theirs)))?;
+        if chars.get(1) != Some(&' ') {
+            return Err(line.error_at(2, "a space"));
+        }
+        let code = ours
+            .chars()
+            .position(|c| Some(&c) == chars.get(2))
+            .ok_or_else(|| line.error_at(3, &letters(ours)))?;
+        if chars.len() > 3 {
+            return Err(line.error_at(4, "the end of the line"));
+        }
+        output.push(Round {
+            them: shapes[them],
+            code,
+        });
     }
-    score
-}
-
-#[aoc(day2, part2)]
-pub fn part2(input: &[(char, char)]) -> u64 {
-    let mut score = 0;
-    for ll in input {
-        score += match ll {
-            ('A', 'X') => 3,
-            ('A', 'Y') => 1 + 3,
-            ('A', 'Z') => 2 + 6,
-            ('B', 'X') => 1,
-            ('B', 'Y') => 2 + 3,
-            ('B', 'Z') => 3 + 6,
-            ('C', 'X') => 2,
-            ('C', 'Y') => 3 + 3,
-            ('C', 'Z') => 1 + 6,
-            _ => 0,
-        };
+    Ok(output)
+}
+
+pub fn load_input(input: &str) -> Result<Vec<Round>, ParseError> {
+    load_with(input, &Classic)
+}
+
+/// What our letters are taken to mean, which the guide never says.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decoding {
+    /// The shape to play for each letter, in the order of the rule set's
+    /// `codes`.
+    Shapes(Vec<Shape>),
+    /// How the round should end for X, Y and Z.
+    Outcomes([Outcome; 3]),
+}
+
+impl Decoding {
+    /// Part 1's reading: each letter is the shape at the same place.
+    pub fn as_shapes(rules: &impl RuleSet) -> Self {
+        Decoding::Shapes(rules.shapes().to_vec())
+    }
+
+    /// Part 2's reading: X, Y and Z are lose, draw and win.
+    pub fn as_outcomes() -> Self {
+        Decoding::Outcomes(Outcome::ALL)
+    }
+
+    /// Every way to read our letters as shapes, then every way to read X, Y
+    /// and Z as outcomes.
+    pub fn all(rules: &impl RuleSet) -> Vec<Self> {
+        let shapes = rules.shapes();
+        let outcomes = Outcome::ALL.iter().copied().permutations(3);
+        shapes
+            .iter()
+            .copied()
+            .permutations(shapes.len())
+            .map(Decoding::Shapes)
+            .chain(outcomes.map(|o| Decoding::Outcomes([o[0], o[1], o[2]])))
+            .collect()
+    }
+
+    /// What we play against `them` for our letter at `code`. Panics if an
+    /// outcome is asked of a letter before X, which only variants with more
+    /// shapes have.
+    pub fn play(&self, rules: &impl RuleSet, them: Shape, code: usize) -> Shape {
+        match self {
+            Decoding::Shapes(shapes) => shapes[code],
+            Decoding::Outcomes(outcomes) => {
+                let outcome = code
+                    .checked_sub(rules.codes().len() - 3)
+                    .map(|i| outcomes[i])
+                    .expect("outcomes are only X, Y or Z");
+                rules.respond(them, outcome)
+            }
+        }
+    }
+}
+
+/// Total score following the guide as read by `decoding`.
+pub fn score(input: &[Round], rules: &impl RuleSet, decoding: &Decoding) -> u64 {
+    input
+        .iter()
+        .map(|round| rules.score(decoding.play(rules, round.them, round.code), round.them))
+        .sum()
+}
+
+/// Total score reading our letters as the shapes to play.
+pub fn score_as_shapes(input: &[Round], rules: &impl RuleSet) -> u64 {
+    score(input, rules, &Decoding::as_shapes(rules))
+}
+
+/// Total score reading X, Y and Z as how each round should end.
+pub fn score_as_outcomes(input: &[Round], rules: &impl RuleSet) -> u64 {
+    score(input, rules, &Decoding::as_outcomes())
+}
+
+/// The most we could score against the opponent's column, ignoring ours.
+pub fn best_score(input: &[Round], rules: &impl RuleSet) -> u64 {
+    input
+        .iter()
+        .map(|round| {
+            let scores = rules.shapes().iter().map(|&us| rules.score(us, round.them));
+            scores.max().unwrap()
+        })
+        .sum()
+}
+
+/// How a guide scores under every reading, next to the best possible.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Analysis {
+    pub best: u64,
+    /// Highest first.
+    pub scores: Vec<(Decoding, u64)>,
+}
+
+/// Outcome readings are left out if the guide uses letters before X.
+pub fn analyze(input: &[Round], rules: &impl RuleSet) -> Analysis {
+    let x = rules.codes().len() - 3;
+    let xyz = input.iter().all(|round| round.code >= x);
+    let mut scores: Vec<_> = Decoding::all(rules)
+        .into_iter()
+        .filter(|decoding| xyz || matches!(decoding, Decoding::Shapes(_)))
+        .map(|decoding| {
+            let score = score(input, rules, &decoding);
+            (decoding, score)
+        })
+        .collect();
+    scores.sort_by_key(|(_, score)| Reverse(*score));
+    Analysis {
+        best: best_score(input, rules),
+        scores,
+    }
+}
+
+/// The opponent's shapes that leave us the fewest points if we keep to
+/// our column as read by `decoding`. Rounds don't affect each other, so
+/// each gets whichever shape hurts most, the first in order on a tie.
+pub fn adversary(input: &[Round], rules: &impl RuleSet, decoding: &Decoding) -> Vec<Shape> {
+    input
+        .iter()
+        .map(|round| {
+            let shapes = rules.shapes().iter().copied();
+            shapes
+                .min_by_key(|&them| rules.score(decoding.play(rules, them, round.code), them))
+                .unwrap()
+        })
+        .collect()
+}
+
+pub fn part1(input: &[Round]) -> u64 {
+    score_as_shapes(input, &Classic)
+}
+
+pub fn part2(input: &[Round]) -> u64 {
+    score_as_outcomes(input, &Classic)
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Round>;
+    type Answer1 = u64;
+    type Answer2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
     }
-    score
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/02a.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(2, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 15);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/02a.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(2, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 12);
     }
+
+    #[test]
+    fn test_load_input() {
+        let err = load_input("A Y\nD X\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+        assert_eq!(err.expected, "A, B or C");
+        let err = load_input("A W").unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (3, "X, Y or Z"));
+        assert_eq!(load_input("AX").unwrap_err().column, 2);
+        assert_eq!(load_input("A XY").unwrap_err().column, 4);
+
+        let rounds = load_with("E V\n", &LizardSpock).unwrap();
+        assert_eq!(rounds[0].them, Shape::Spock);
+        assert_eq!(rounds[0].code, 0);
+    }
+
+    #[test]
+    fn test_rule_sets() {
+        let rules = LizardSpock;
+        for &a in rules.shapes() {
+            let wins = rules.shapes().iter().filter(|&&b| rules.beats(a, b));
+            assert_eq!(wins.count(), 2);
+            assert!(!rules.beats(a, a));
+        }
+        assert_eq!(rules.outcome(Shape::Lizard, Shape::Spock), Outcome::Win);
+        assert_eq!(rules.respond(Shape::Rock, Outcome::Win), Shape::Paper);
+        assert_eq!(rules.score(Shape::Spock, Shape::Rock), 5 + 6);
+
+        let rounds = load_with("A Z\nB V\nC X", &rules).unwrap();
+        assert_eq!(score_as_shapes(&rounds, &rules), (5 + 6) + 1 + (3 + 3));
+        let rounds = load_with("E X\nD Z", &rules).unwrap();
+        assert_eq!(score_as_outcomes(&rounds, &rules), 1 + (1 + 6));
+
+        // Draws worth nothing and a flat point per shape
+        let scored = Scored {
+            rules: Classic,
+            shape_points: vec![1, 1, 1],
+            outcome_points: [0, 0, 6],
+        };
+        let input = load(2, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
+        assert_eq!(score_as_shapes(&input, &scored), 3 + 6);
+        assert_eq!(score_as_outcomes(&input, &scored), 3 + 6);
+    }
+
+    #[test]
+    fn test_analyze() {
+        let input = load(2, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
+        let analysis = analyze(&input, &Classic);
+        assert_eq!(analysis.best, 8 + 9 + 7);
+        assert_eq!(analysis.scores.len(), 12);
+        assert!(analysis.scores.iter().all(|(_, s)| *s <= analysis.best));
+        let part1 = Decoding::as_shapes(&Classic);
+        let part2 = Decoding::as_outcomes();
+        assert!(analysis.scores.contains(&(part1.clone(), 15)));
+        assert!(analysis.scores.contains(&(part2.clone(), 12)));
+        // Always winning reaches the best
+        let win = Decoding::Outcomes([Outcome::Win; 3]);
+        assert_eq!(score(&input, &Classic, &win), analysis.best);
+
+        // Playing paper, rock, scissors, the opponent picks what beats them
+        let them = adversary(&input, &Classic, &part1);
+        assert_eq!(them, vec![Shape::Scissors, Shape::Paper, Shape::Rock]);
+        let rounds: Vec<Round> = input
+            .iter()
+            .zip(&them)
+            .map(|(round, &them)| Round { them, ..*round })
+            .collect();
+        assert_eq!(score_as_shapes(&rounds, &Classic), 2 + 1 + 3);
+
+        // Outcomes are fixed, so only the shape we're made to play matters
+        let them = adversary(&input, &Classic, &part2);
+        assert_eq!(them, vec![Shape::Rock, Shape::Paper, Shape::Scissors]);
+
+        let input = load_with("A Y\nB X\nC Z", &LizardSpock).unwrap();
+        assert_eq!(analyze(&input, &LizardSpock).scores.len(), 120 + 6);
+        let input = load_with("A Y\nB V", &LizardSpock).unwrap();
+        assert_eq!(analyze(&input, &LizardSpock).scores.len(), 120);
+    }
 }
@@ -1,132 +1,97 @@
-use aoc_helpers::linked_list::*;
-use std::collections::{HashMap, HashSet};
-use std::fmt::{Debug, Display};
-use std::io::stdin;
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
+use log::{debug, trace};
 
 type Input = i64;
 
-#[aoc_generator(day20)]
-fn load_input(input: &str) -> Vec<Input> {
+fn load_input(input: &str) -> Result<Vec<Input>, ParseError> {
     let mut output = Vec::new();
-    for line in input.lines() {
-        let value = line.trim_end().parse::<i64>().unwrap();
+    let mut lines = Lines::new(20, input);
+    for line in lines.by_ref() {
+        let value = line.parse::<i64>(line.text.trim_end(), "an integer")?;
         output.push(value);
     }
-    output
-}
-
-fn ll_get<T>(list: &LinkedList<T>, idx: usize) -> usize {
-    let mut nid = list.head.unwrap();
-    for _ in 0..idx {
-        nid = list.data[nid].tail.unwrap();
+    // A lone number has nowhere to move to, and the answer counts from 0
+    if output.len() < 2 {
+        return Err(lines.next_or("another integer").unwrap_err());
     }
-    nid
-}
-
-fn ll_find<T: PartialEq>(list: &LinkedList<T>, value: T) -> usize {
-    let mut nid = list.head.unwrap();
-    loop {
-        if list.data[nid].value == value {
-            return nid;
-        } else {
-            nid = list.data[nid].next().unwrap();
-        }
+    if !output.contains(&0) {
+        return Err(lines.next_or("a `0` somewhere in the list").unwrap_err());
     }
+    Ok(output)
 }
 
-fn ll_print<T: Debug>(list: &LinkedList<T>) {
-    let mut nid = list.head.unwrap();
-    let first_id = nid;
-    println!();
-    loop {
-        print!("{:?} ", list.data[nid].value);
-        if let Some(nnid) = list.data[nid].next() {
-            nid = nnid;
-        }
-        if nid == first_id {
-            break;
-        }
+/// Move every number along by its value, in the order they started in.
+fn mix(input: &[Input]) -> Vec<Input> {
+    // Shuffle indices rather than values, since values repeat
+    let mut order: Vec<usize> = (0..input.len()).collect();
+    let places = input.len() as i64 - 1;
+    for (i, &value) in input.iter().enumerate() {
+        let from = order.iter().position(|&j| j == i).unwrap();
+        order.remove(from);
+        let to = (from as i64 + value).rem_euclid(places) as usize;
+        order.insert(to, i);
+        trace!("{:?}", order.iter().map(|&j| input[j]).collect::<Vec<_>>());
     }
-    println!();
+    order.into_iter().map(|i| input[i]).collect()
 }
 
-// 1467 is too low
-#[aoc(day20, part1)]
 fn part1(input: &[Input]) -> i64 {
-    // Create a linked list
-    let mut list = LinkedList::<i64>::with_capacity(16 * input.len());
-    let n = input.len();
-    for value in input {
-        list.push_tail(*value);
-    }
+    let mixed = mix(input);
+    let zero = mixed.iter().position(|&value| value == 0).unwrap();
+    let nums: Vec<i64> = [1000, 2000, 3000]
+        .iter()
+        .map(|offset| mixed[(zero + offset) % mixed.len()])
+        .collect();
+    debug!("nums: {:?}", nums);
 
-    // Make it circular
-    let head_id = list.head.unwrap();
-    let tail_id = list.tail.unwrap();
-    list.data[head_id].head = Some(tail_id);
-    list.data[tail_id].tail = Some(head_id);
-
-    for value in input {
-        ll_print(&list);
-        let mut nid = ll_find(&list, *value);
-        //println!("nid: {}", nid);
-        nid = list.data[nid].prev().unwrap();
-
-        let temp = list.pop_id(list.data[nid].next().unwrap());
-        //println!("temp: {}", temp);
+    nums.iter().sum()
+}
 
-        // nid currently pointing at the element in list after the one that
-        // just got popped.
-        if *value > 0 {
-            for _ in 0..*value {
-                nid = list.data[nid].tail.unwrap();
-            }
-            list.insert_after(temp, nid);
-        } else {
-            for _ in 0..(*value).abs() {
-                nid = list.data[nid].head.unwrap();
-            }
-            list.insert_after(temp, nid);
-        }
-    }
+fn part2(_input: &[Input]) -> i64 {
+    0
+}
 
-    ll_print(&list);
+pub struct Day20;
 
-    let mut nid = ll_find(&list, 0);
-    let mut nums = vec![];
+impl Solution for Day20 {
+    type Input = Vec<Input>;
+    type Answer1 = i64;
+    type Answer2 = i64;
 
-    for _ in 0..3 {
-        for _ in 0..1000 {
-            nid = list.data[nid].next().unwrap();
-        }
-        nums.push(list.data[nid].value);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
     }
-    println!("nums: {:?}", nums);
 
-    nums.iter().sum()
-}
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
 
-#[aoc(day20, part2)]
-fn part2(input: &[Input]) -> i64 {
-    0
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/20.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(20, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 3);
     }
 
     #[test]
-    fn test_part2() {
-        let input = read_to_string("input/2022/20.txt").unwrap();
-        let input = load_input(&input);
-        assert_eq!(part2(&input), 0);
+    fn test_load_input() {
+        assert_eq!(load_input("3\n0\n").unwrap(), vec![3, 0]);
+        assert_eq!(load_input("0\n").unwrap_err().expected, "another integer");
+        let error = load_input("1\n2\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (3, "a `0` somewhere in the list")
+        );
     }
 }
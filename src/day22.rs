@@ -1,43 +1,35 @@
-use std::collections::HashMap;
-use std::io::stdin;
-
-#[derive(Clone, Copy, Debug)]
-enum Tile {
+use crate::geom::{Direction4, Point2};
+use crate::grid::Grid;
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
+use crate::viz::{Cell, Colour, Frame, Viz};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tile {
+    /// Off the edge of the map
+    Void,
     Floor,
     Wall,
 }
 
-#[derive(Clone, Copy, Debug)]
-enum Direction {
-    North,
-    East,
-    South,
-    West,
-}
-
 #[derive(Clone, Copy, Debug)]
 struct Player {
-    dir: Direction,
-    pos: (usize, usize),
+    dir: Direction4,
+    pos: Point2<usize>,
 }
 
 impl Player {
-    fn new(map: &HashMap<(usize, usize), Tile>) -> Self {
-        for col in 1..205 {
-            if let Some(_) = map.get(&(1, col)) {
-                return Self {
-                    dir: Direction::East,
-                    pos: (1, col),
-                };
-            }
+    fn new(map: &Grid<Tile>) -> Self {
+        Self {
+            dir: Direction4::East,
+            pos: map.find(|tile| *tile == Tile::Floor).unwrap(),
         }
-        unreachable!();
     }
 
     /// This method assumes you've already found a valid next position to try,
     /// and will crash if you did not.
-    fn try_move(&mut self, next_pos: (usize, usize), map: &HashMap<(usize, usize), Tile>) -> bool {
-        match map.get(&next_pos).unwrap() {
+    fn try_move(&mut self, next_pos: Point2<usize>, map: &Grid<Tile>) -> bool {
+        match map[next_pos] {
             Tile::Floor => {
                 // We can move there
                 self.pos = next_pos;
@@ -47,230 +39,145 @@ impl Player {
                 // We cannot move there, so just bail
                 false
             }
+            Tile::Void => unreachable!(),
         }
     }
 
-    fn move_forward(&mut self, map: &HashMap<(usize, usize), Tile>) {
-        let next_pos = match self.dir {
-            Direction::North => (self.pos.0 - 1, self.pos.1),
-            Direction::East => (self.pos.0, self.pos.1 + 1),
-            Direction::South => (self.pos.0 + 1, self.pos.1),
-            Direction::West => (self.pos.0, self.pos.1 - 1),
-        };
+    /// The next position ahead, if it is still on the map.
+    fn ahead(&self, map: &Grid<Tile>) -> Option<Point2<usize>> {
+        self.pos
+            .checked_step(self.dir)
+            .filter(|&p| map.get(p).is_some_and(|&tile| tile != Tile::Void))
+    }
 
-        if let Some(_) = map.get(&next_pos) {
+    fn move_forward(&mut self, map: &Grid<Tile>) {
+        if let Some(next_pos) = self.ahead(map) {
             // Tile is in the map
             self.try_move(next_pos, map);
         } else {
-            // Tile is not in the map, meaning wrap
-            match self.dir {
-                Direction::North => {
-                    // Find a valid row assuming max is something less than 205
-                    for i in 0..205 {
-                        let row = 205 - i;
-                        if let Some(_) = map.get(&(row, self.pos.1)) {
-                            // Found the wrap around
-                            self.try_move((row, self.pos.1), map);
-                            break;
-                        }
-                    }
-                }
-                Direction::East => {
-                    // Find a valid col assuming starting at 1
-                    for col in 1..205 {
-                        if let Some(_) = map.get(&(self.pos.0, col)) {
-                            // Found the wrap around
-                            self.try_move((self.pos.0, col), map);
-                            break;
-                        }
-                    }
-                }
-                Direction::South => {
-                    // Find a valid row assuming max is something less than 205
-                    for row in 1..205 {
-                        if let Some(_) = map.get(&(row, self.pos.1)) {
-                            // Found the wrap around
-                            self.try_move((row, self.pos.1), map);
-                            break;
-                        }
-                    }
-                }
-                Direction::West => {
-                    // Find a valid col assuming max is something less than 205
-                    for i in 0..205 {
-                        let col = 205 - i;
-                        if let Some(_) = map.get(&(self.pos.0, col)) {
-                            // Found the wrap around
-                            self.try_move((self.pos.0, col), map);
-                            break;
-                        }
-                    }
-                }
-            }
+            // Tile is not in the map, meaning wrap to the far side of this
+            // row or column
+            let wrapped = map
+                .ray(self.pos, self.dir.reverse())
+                .take_while(|&p| map[p] != Tile::Void)
+                .last()
+                .unwrap_or(self.pos);
+            self.try_move(wrapped, map);
         }
     }
 
     /// We're going to hardcode this because yeah.
     ///
-    fn move_forward2(&mut self, map: &HashMap<(usize, usize), Tile>) {
+    fn move_forward2(&mut self, map: &Grid<Tile>) {
         // faces are (0, 1), (0, 2), (1, 1), (2, 0), (2, 1), (3, 0)
         // if hface = 1 then it just rolls up. hface = 0 is left side, hface = 1 is right
-        let vface = (self.pos.0 - 1) / 50; // 0, 1, 2, or 3
-        let hface = (self.pos.1 - 1) / 50; // 0, 1, or 2
-        let next_pos = match self.dir {
-            Direction::North => (self.pos.0 - 1, self.pos.1),
-            Direction::East => (self.pos.0, self.pos.1 + 1),
-            Direction::South => (self.pos.0 + 1, self.pos.1),
-            Direction::West => (self.pos.0, self.pos.1 - 1),
-        };
-
-        if let Some(_) = map.get(&next_pos) {
+        let vface = self.pos.y / 50; // 0, 1, 2, or 3
+        let hface = self.pos.x / 50; // 0, 1, or 2
+
+        if let Some(next_pos) = self.ahead(map) {
             // Tile is in the map
             self.try_move(next_pos, map);
         } else {
-            // Tile is not in the map, meaning wrap
-            match self.dir {
-                Direction::North => {
-                    let (try_pos, dir): ((usize, usize), Direction) = match hface {
-                        0 => ((50 + self.pos.1, 51), Direction::East),
-                        1 => ((150 + (self.pos.1 - 50), 1), Direction::East),
-                        2 => ((200, self.pos.1 - 100), Direction::North),
-                        _ => unreachable!(),
-                    };
-                    if self.try_move(try_pos, map) {
-                        // Change direction accordingly
-                        self.dir = dir;
-                    }
-                }
-                Direction::East => {
-                    let (try_pos, dir): ((usize, usize), Direction) = match vface {
-                        0 => ((151 - self.pos.0, 100), Direction::West),
-                        1 => ((50, 100 + (self.pos.0 - 50)), Direction::North),
-                        2 => ((51 - (self.pos.0 - 100), 150), Direction::West),
-                        3 => ((150, 50 + (self.pos.0 - 150)), Direction::North),
-                        _ => unreachable!(),
-                    };
-                    if self.try_move(try_pos, map) {
-                        // Change direction accordingly
-                        self.dir = dir;
-                    }
-                }
-                Direction::South => {
-                    let (try_pos, dir): ((usize, usize), Direction) = match hface {
-                        0 => ((1, self.pos.1 + 100), Direction::South),
-                        1 => ((150 + (self.pos.1 - 50), 50), Direction::West),
-                        2 => ((50 + (self.pos.1 - 100), 100), Direction::West),
-                        _ => unreachable!(),
-                    };
-                    if self.try_move(try_pos, map) {
-                        // Change direction accordingly
-                        self.dir = dir;
-                    }
-                }
-                Direction::West => {
-                    let (try_pos, dir): ((usize, usize), Direction) = match vface {
-                        0 => ((151 - self.pos.0, 1), Direction::East),
-                        1 => ((101, self.pos.0 - 50), Direction::South),
-                        2 => ((51 - (self.pos.0 - 100), 51), Direction::East),
-                        3 => ((1, 50 + self.pos.0 - 150), Direction::South),
-                        _ => unreachable!(),
-                    };
-                    if self.try_move(try_pos, map) {
-                        // Change direction accordingly
-                        self.dir = dir;
-                    }
-                }
+            // Tile is not in the map, meaning wrap. The edges below were
+            // worked out counting rows and columns from 1.
+            let (x, y) = (self.pos.x + 1, self.pos.y + 1);
+            let (try_pos, dir) = match self.dir {
+                Direction4::North => match hface {
+                    0 => (Point2::new(51, 50 + x), Direction4::East),
+                    1 => (Point2::new(1, 150 + (x - 50)), Direction4::East),
+                    2 => (Point2::new(x - 100, 200), Direction4::North),
+                    _ => unreachable!(),
+                },
+                Direction4::East => match vface {
+                    0 => (Point2::new(100, 151 - y), Direction4::West),
+                    1 => (Point2::new(100 + (y - 50), 50), Direction4::North),
+                    2 => (Point2::new(150, 51 - (y - 100)), Direction4::West),
+                    3 => (Point2::new(50 + (y - 150), 150), Direction4::North),
+                    _ => unreachable!(),
+                },
+                Direction4::South => match hface {
+                    0 => (Point2::new(x + 100, 1), Direction4::South),
+                    1 => (Point2::new(50, 150 + (x - 50)), Direction4::West),
+                    2 => (Point2::new(100, 50 + (x - 100)), Direction4::West),
+                    _ => unreachable!(),
+                },
+                Direction4::West => match vface {
+                    0 => (Point2::new(1, 151 - y), Direction4::East),
+                    1 => (Point2::new(y - 50, 101), Direction4::South),
+                    2 => (Point2::new(51, 51 - (y - 100)), Direction4::East),
+                    3 => (Point2::new(50 + y - 150, 1), Direction4::South),
+                    _ => unreachable!(),
+                },
+            };
+            if self.try_move(try_pos - Point2::new(1, 1), map) {
+                // Change direction accordingly
+                self.dir = dir;
             }
         }
     }
 
     fn steer(&mut self, steer: char) {
-        match steer {
-            'L' => match self.dir {
-                Direction::North => self.dir = Direction::West,
-                Direction::East => self.dir = Direction::North,
-                Direction::South => self.dir = Direction::East,
-                Direction::West => self.dir = Direction::South,
-            },
-            'R' => match self.dir {
-                Direction::North => self.dir = Direction::East,
-                Direction::East => self.dir = Direction::South,
-                Direction::South => self.dir = Direction::West,
-                Direction::West => self.dir = Direction::North,
-            },
+        self.dir = match steer {
+            'L' => self.dir.turn_left(),
+            'R' => self.dir.turn_right(),
             _ => unreachable!(),
         }
     }
 }
 
-type Input = (String, HashMap<(usize, usize), Tile>);
+type Input = (String, Grid<Tile>);
 
-#[aoc_generator(day22)]
-fn load_input(input: &str) -> Input {
-    let mut output = HashMap::new();
-    let mut line_iter = input.lines().enumerate();
+fn load_input(input: &str) -> Result<Input, ParseError> {
+    let mut lines = Lines::new(22, input);
+    let mut rows = vec![];
     loop {
-        let (row, line) = line_iter.next().unwrap();
-        for (col, c) in line.chars().enumerate() {
-            match c {
-                '.' => {
-                    output.insert((row + 1, col + 1), Tile::Floor);
-                }
-                '#' => {
-                    output.insert((row + 1, col + 1), Tile::Wall);
-                }
-                ' ' => (),
-                _ => break,
-            }
-        }
-        if line == "" {
+        let line = lines.next_or("a blank line after the map")?;
+        if line.text.is_empty() {
             break;
         }
+        rows.push(line);
+    }
+    let map = Grid::from_lines(rows, "`.`, `#` or a space", |c| match c {
+        '.' => Some(Tile::Floor),
+        '#' => Some(Tile::Wall),
+        ' ' => Some(Tile::Void),
+        _ => None,
+    })?;
+    let line = lines.next_or("the path instructions")?;
+    for (col, c) in line.text.trim_end().chars().enumerate() {
+        if !(c.is_ascii_digit() || c == 'L' || c == 'R') {
+            return Err(line.error_at(col + 1, "a number, `L` or `R`"));
+        }
     }
-    let instructions = line_iter.next().unwrap().1.to_string();
-    (instructions, output)
+    Ok((line.text.to_string(), map))
 }
 
-fn draw_board(player: &Player, map: &HashMap<(usize, usize), Tile>, num: &str, instructions: &str) {
-    println!();
-    println!("Instructions: {}", instructions);
-    println!("Number: {}", num);
-    println!();
-
-    let rstart = if player.pos.0 < 19 {
-        0
-    } else {
-        player.pos.0 - 20
-    };
-
-    let rstop = if player.pos.0 > 180 {
-        205
-    } else {
-        player.pos.0 + 20
-    };
+fn draw_board(player: &Player, map: &Grid<Tile>, num: &str) -> Frame {
+    let rstart = player.pos.y.saturating_sub(20);
+    let rstop = (player.pos.y + 20).min(map.height());
 
+    let mut frame =
+        Frame::new(map.width(), rstop - rstart, ' ').caption(format!("Number: {}", num));
     for row in rstart..rstop {
-        for col in 1..180 {
-            if (row, col) == player.pos {
-                print!("P");
-                continue;
-            }
-            let mut c = ' ';
-            if let Some(tile) = map.get(&(row, col)) {
-                c = match tile {
-                    Tile::Floor => '.',
-                    Tile::Wall => '#',
-                }
-            }
-            print!("{}", c);
+        for (col, tile) in map.row(row).iter().enumerate() {
+            let c = match tile {
+                Tile::Void => ' ',
+                Tile::Floor => '.',
+                Tile::Wall => '#',
+            };
+            frame.put(col, row - rstart, c);
         }
-        println!();
     }
+    frame.set(
+        player.pos.x,
+        player.pos.y - rstart,
+        Cell::coloured('P', Colour::RED),
+    );
+    frame
 }
 
-// 106158 is too high
-#[aoc(day22, part1)]
-fn part1(input: &Input) -> usize {
+/// Follow the instructions on the flat map.
+fn walk(input: &Input, viz: &mut Viz) -> usize {
     let (instructions, map) = input;
 
     let mut player = Player::new(map);
@@ -279,14 +186,18 @@ fn part1(input: &Input) -> usize {
     let mut char_iter = instructions.chars();
     let mut num = String::new();
     loop {
-        //draw_board(&player, &map, &num, &instructions);
-        //let mut asdf = String::new();
-        //stdin().read_line(&mut asdf);
+        viz.show(|| draw_board(&player, map, &num));
+        if !viz.tick(&[
+            ("row", player.pos.y as i64 + 1),
+            ("col", player.pos.x as i64 + 1),
+        ]) {
+            break;
+        }
 
         if let Some(c) = char_iter.next() {
             if c.is_alphabetic() {
                 // Make the last move, if any
-                if num.len() > 0 {
+                if !num.is_empty() {
                     let move_len = num.parse::<usize>().unwrap();
                     num = String::new();
                     for _ in 0..move_len {
@@ -310,100 +221,106 @@ fn part1(input: &Input) -> usize {
     }
 
     // Make the last move, if any
-    if num.len() > 0 {
+    if !num.is_empty() {
         let move_len = num.parse::<usize>().unwrap();
-        num = String::new();
         for _ in 0..move_len {
             player.move_forward(map);
         }
     }
 
     let facing = match player.dir {
-        Direction::North => 3,
-        Direction::East => 0,
-        Direction::South => 1,
-        Direction::West => 2,
+        Direction4::North => 3,
+        Direction4::East => 0,
+        Direction4::South => 1,
+        Direction4::West => 2,
     };
-    player.pos.0 * 1000 + player.pos.1 * 4 + facing
+    (player.pos.y + 1) * 1000 + (player.pos.x + 1) * 4 + facing
+}
+
+fn part1(input: &Input) -> usize {
+    walk(input, &mut Viz::off())
 }
 
-// 164086 is too high
-#[aoc(day22, part2)]
 fn part2(input: &Input) -> usize {
     let (instructions, map) = input;
 
     let mut player = Player::new(map);
 
     // Execute instructions
-    let mut char_iter = instructions.chars();
     let mut num = String::new();
-    loop {
-        //draw_board(&player, &map, &num, &instructions);
-        //let mut asdf = String::new();
-        //stdin().read_line(&mut asdf);
-
-        if let Some(c) = char_iter.next() {
-            if c.is_alphabetic() {
-                // Make the last move, if any
-                if num.len() > 0 {
-                    let move_len = num.parse::<usize>().unwrap();
-                    num = String::new();
-                    for _ in 0..move_len {
-                        player.move_forward2(map);
-                    }
+    for c in instructions.chars() {
+        if c.is_alphabetic() {
+            // Make the last move, if any
+            if !num.is_empty() {
+                let move_len = num.parse::<usize>().unwrap();
+                num = String::new();
+                for _ in 0..move_len {
+                    player.move_forward2(map);
                 }
-
-                // Steering
-                player.steer(c);
-            } else if c.is_alphanumeric() {
-                // Gather characters into the move length string
-                num.push(c);
-            } else {
-                // Probably a new line at the end, bail
-                break;
             }
+
+            // Steering
+            player.steer(c);
+        } else if c.is_alphanumeric() {
+            // Gather characters into the move length string
+            num.push(c);
         } else {
-            // iter is empty, bail
+            // Probably a new line at the end, bail
             break;
         }
     }
 
     // Make the last move, if any
-    if num.len() > 0 {
+    if !num.is_empty() {
         let move_len = num.parse::<usize>().unwrap();
-        num = String::new();
         for _ in 0..move_len {
             player.move_forward2(map);
         }
     }
 
     let facing = match player.dir {
-        Direction::North => 3,
-        Direction::East => 0,
-        Direction::South => 1,
-        Direction::West => 2,
+        Direction4::North => 3,
+        Direction4::East => 0,
+        Direction4::South => 1,
+        Direction4::West => 2,
     };
-    player.pos.0 * 1000 + player.pos.1 * 4 + facing
+    (player.pos.y + 1) * 1000 + (player.pos.x + 1) * 4 + facing
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+
+    fn visualize(input: &Self::Input, viz: &mut Viz) -> bool {
+        walk(input, viz);
+        true
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/22.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(22, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 6032);
     }
-
-    /* lol im not going to solve this for general case foldings
-    #[test]
-    fn test_part2() {
-        let input = read_to_string("input/2022/22.txt").unwrap();
-        let input = load_input(&input);
-        assert_eq!(part2(&input), 0);
-    }
-    */
 }
@@ -1,108 +1,67 @@
+use crate::geom::{Direction4, Direction8, Point2};
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
+use crate::sparse::SparseGrid;
+use crate::viz::{Frame, Viz};
+use log::trace;
 use std::collections::{HashMap, HashSet};
-use std::fmt;
-use std::io::stdin;
-use std::{thread, time};
-
-#[derive(Clone, Copy, Debug)]
-enum Direction {
-    North,
-    South,
-    West,
-    East,
-}
 
-type Input = HashSet<(i64, i64)>;
+type Input = SparseGrid;
 
-#[aoc_generator(day23)]
-fn load_input(input: &str) -> Input {
-    let mut output = HashSet::new();
-    for (row, line) in input.lines().enumerate() {
-        for (col, c) in line.chars().enumerate() {
+fn load_input(input: &str) -> Result<Input, ParseError> {
+    let mut output = SparseGrid::new();
+    for (row, line) in Lines::new(23, input).enumerate() {
+        for (col, c) in line.text.chars().enumerate() {
             match c {
                 '#' => {
-                    output.insert((row as i64, col as i64));
+                    output.insert(Point2::new(col as i64, row as i64), ());
                 }
-                _ => (),
+                '.' => (),
+                _ => return Err(line.error_at(col + 1, "`#` or `.`")),
             }
         }
     }
-    output
+    Ok(output)
 }
 
-fn get_neighbors(p: (i64, i64)) -> Vec<(i64, i64)> {
-    let mut output = vec![];
-
-    // NW, N, NE = 0..3
-    output.push((p.0 - 1, p.1 - 1));
-    output.push((p.0 - 1, p.1));
-    // NE, E, SE = 2..5
-    output.push((p.0 - 1, p.1 + 1));
-    output.push((p.0, p.1 + 1));
-    // SE, S, SW = 4..7
-    output.push((p.0 + 1, p.1 + 1));
-    output.push((p.0 + 1, p.1));
-    // SW, W, NW = 6..8, 0
-    output.push((p.0 + 1, p.1 - 1));
-    output.push((p.0, p.1 - 1));
-
-    output
-}
+/// Directions the elves consider moving in, in order for the first round.
+/// Each round starts one further along.
+const PROPOSALS: [Direction4; 4] = [
+    Direction4::North,
+    Direction4::South,
+    Direction4::West,
+    Direction4::East,
+];
 
-fn next_direction(p: (i64, i64), round_idx: usize, map: &Input) -> Option<Direction> {
-    let n = get_neighbors(p);
-    let n_idx = vec![0_usize, 1, 2];
-    let s_idx = vec![4_usize, 5, 6];
-    let w_idx = vec![0_usize, 6, 7];
-    let e_idx = vec![2_usize, 3, 4];
-
-    let m = round_idx % 4;
-    let mut c_idxs = vec![
-        (n_idx, Direction::North),
-        (s_idx, Direction::South),
-        (w_idx, Direction::West),
-        (e_idx, Direction::East),
-    ];
-
-    if n.iter().all(|x| !map.contains(x)) {
+fn next_direction(p: Point2<i64>, round_idx: usize, map: &Input) -> Option<Direction4> {
+    let free = |dir: Direction8| !map.contains(p.step(dir));
+
+    if Direction8::ALL.into_iter().all(free) {
         // If all neighbors do not contain an Elf, do nothing.
-        None
-    } else {
-        if c_idxs[m % 4].0.iter().map(|&i| n[i]).all(|x| !map.contains(&x)) {
-            // If all x options do not contain an Elf, go x
-            Some(c_idxs[m % 4].1)
-        } else if c_idxs[(m + 1) % 4].0.iter().map(|&i| n[i]).all(|x| !map.contains(&x)) {
-            // If all x options do not contain an Elf, go x
-            Some(c_idxs[(m + 1) % 4].1)
-        } else if c_idxs[(m + 2) % 4].0.iter().map(|&i| n[i]).all(|x| !map.contains(&x)) {
-            // If all x options do not contain an Elf, go x
-            Some(c_idxs[(m + 2) % 4].1)
-        } else if c_idxs[(m + 3) % 4].0.iter().map(|&i| n[i]).all(|x| !map.contains(&x)) {
-            // If all x options do not contain an Elf, go x
-            Some(c_idxs[(m + 3) % 4].1)
-        } else {
-            // Too many elves around, just stay where you are
-            None
-        }
+        return None;
     }
+    // Go the first way with no Elf ahead or diagonally ahead. If there are
+    // too many elves around, just stay where you are.
+    (0..4).map(|i| PROPOSALS[(round_idx + i) % 4]).find(|&dir| {
+        let ahead = Direction8::from(dir);
+        [ahead.turn_left(), ahead, ahead.turn_right()]
+            .into_iter()
+            .all(free)
+    })
 }
 
-fn next_pos(orig_pos: (i64, i64), dir: Option<Direction>) -> (i64, i64) {
-    match dir {
-        None => orig_pos,
-        Some(Direction::North) => (orig_pos.0 - 1, orig_pos.1),
-        Some(Direction::East) => (orig_pos.0, orig_pos.1 + 1),
-        Some(Direction::South) => (orig_pos.0 + 1, orig_pos.1),
-        Some(Direction::West) => (orig_pos.0, orig_pos.1 - 1),
-    }
+fn next_pos(orig_pos: Point2<i64>, dir: Option<Direction4>) -> Point2<i64> {
+    dir.map_or(orig_pos, |dir| orig_pos.step(dir))
 }
 
-fn round(map: &Input, round_idx: usize) -> Input {
+/// Play one round, returning the new map and whether any elf moved.
+fn round(map: &Input, round_idx: usize) -> (Input, bool) {
     // First half
-    let mut next_dirs: Vec<((i64, i64), Option<Direction>)> = vec![];
-    for orig_pos in map {
-        next_dirs.push((*orig_pos, next_direction(*orig_pos, round_idx, map)));
+    let mut next_dirs: Vec<(Point2<i64>, Option<Direction4>)> = vec![];
+    for orig_pos in map.points() {
+        next_dirs.push((orig_pos, next_direction(orig_pos, round_idx, map)));
     }
-    //println!("{:?}", next_dirs);
+    trace!("proposed: {:?}", next_dirs);
 
     // Second half
 
@@ -112,7 +71,7 @@ fn round(map: &Input, round_idx: usize) -> Input {
         let np = next_pos(*orig_pos, *dir);
         *candidate_map.entry(np).or_insert(0) += 1;
     }
-    //println!("\n{:?}", candidate_map);
+    trace!("wanted: {:?}", candidate_map);
     let mut cancelled_set = HashSet::new();
     for (k, v) in candidate_map {
         // Cancel moves that would lead to collisions
@@ -120,110 +79,106 @@ fn round(map: &Input, round_idx: usize) -> Input {
             cancelled_set.insert(k);
         }
     }
-    //println!("\n{:?}", cancelled_set);
+    trace!("cancelled: {:?}", cancelled_set);
 
     // Execute moves if not in cancelled set
-    let mut next_map = HashSet::new();
+    let mut next_map = SparseGrid::new();
+    let mut moved = false;
     for (orig_pos, dir) in next_dirs {
         let np = next_pos(orig_pos, dir);
         if cancelled_set.contains(&np) {
             // This was cancelled
-            next_map.insert(orig_pos);
+            next_map.insert(orig_pos, ());
         } else {
-            next_map.insert(np);
+            moved |= np != orig_pos;
+            next_map.insert(np, ());
         }
     }
-    next_map
+    (next_map, moved)
+}
+
+fn draw_map(map: &Input, cntr: usize) -> Frame {
+    let bounds = map.bounds().unwrap();
+    let width = bounds.width() as usize;
+    let height = bounds.height() as usize;
+    let mut frame = Frame::new(width, height, '.').caption(format!("Round {}", cntr));
+    for p in map.points() {
+        let p = p - bounds.min;
+        frame.put(p.x as usize, p.y as usize, '#');
+    }
+    frame
+}
+
+fn part1(input: &Input) -> i64 {
+    let mut map = input.clone();
+    for i in 0..10 {
+        map = round(&map, i).0;
+    }
+    map.bounds().unwrap().area() - map.len() as i64
 }
 
-fn get_map_extent(map: &Input) -> (i64, i64, i64, i64) {
-    // Find extent of the map
-    let mut minx = i64::MAX;
-    let mut miny = i64::MAX;
-    let mut maxx = i64::MIN;
-    let mut maxy = i64::MIN;
-    for p in map {
-        if p.0 > maxy {
-            maxy = p.0;
-        } else if p.0 < miny {
-            miny = p.0;
+/// Run rounds until nobody moves, returning the number of the first round
+/// where nobody did, or None if the user quits first.
+fn spread(input: &Input, viz: &mut Viz) -> Option<usize> {
+    let mut map = input.clone();
+    for cntr in 0.. {
+        viz.show(|| draw_map(&map, cntr));
+        if !viz.tick(&[("round", cntr as i64)]) {
+            return None;
         }
-        if p.1 > maxx {
-            maxx = p.1;
-        } else if p.1 < minx {
-            minx = p.1;
+        let (next, moved) = round(&map, cntr);
+        if !moved {
+            return Some(cntr + 1);
         }
+        map = next;
     }
-    (minx, maxx, miny, maxy)
+    unreachable!()
 }
 
-fn draw_map(map: &Input) {
+fn part2(input: &Input) -> usize {
+    spread(input, &mut Viz::off()).unwrap()
+}
 
-    let (minx, maxx, miny, maxy) = get_map_extent(map);
+pub struct Day23;
 
-    // To let test garbage printing finish up before draw
-    thread::sleep(time::Duration::from_millis(10));
+impl Solution for Day23 {
+    type Input = Input;
+    type Answer1 = i64;
+    type Answer2 = usize;
 
-    // Draw it
-    println!();
-    for row in miny..maxy + 1 {
-        for col in minx..maxx + 1 {
-            if map.contains(&(row, col)) {
-                print!("#");
-            } else {
-                print!(".");
-            }
-        }
-        println!();
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
     }
-    println!();
-}
 
-#[aoc(day23, part1)]
-fn part1(input: &Input) -> i64 {
-    let mut map = input.clone();
-    for i in 0..11 {
-        //draw_map(&map);
-        map = round(&map, i);
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
     }
-    let (minx, maxx, miny, maxy) = get_map_extent(&map);
-    let total = (maxx - minx + 1) * (maxy - miny + 1);
-    total - map.len() as i64
-}
 
-#[aoc(day23, part2)]
-fn part2(input: &Input) -> usize {
-    let mut map = input.clone();
-    let mut cntr = 0;
-    loop {
-        //draw_map(&map);
-        let last_map = map.clone();
-        map = round(&map, cntr);
-        cntr += 1;
-
-        if map == last_map {
-            break;
-        }
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+
+    fn visualize(input: &Self::Input, viz: &mut Viz) -> bool {
+        spread(input, viz).is_some()
     }
-    cntr
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/23.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(23, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 110);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/23.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(23, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 20);
     }
 }
@@ -1,8 +1,26 @@
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
 use std::collections::HashSet;
 
-#[aoc_generator(day3)]
-pub fn load_input(input: &str) -> String {
-    String::from(input)
+/// Check every rucksack is letters split evenly between two compartments,
+/// and that they come in groups of three.
+pub fn load_input(input: &str) -> Result<String, ParseError> {
+    let mut lines = Lines::new(3, input);
+    let mut count = 0;
+    for line in lines.by_ref() {
+        if let Some(col) = line.text.find(|c: char| !c.is_ascii_alphabetic()) {
+            return Err(line.error_at(col + 1, "a letter"));
+        }
+        if line.text.is_empty() || line.text.len() % 2 != 0 {
+            let column = line.text.len() + 1;
+            return Err(line.error_at(column, "an even number of items"));
+        }
+        count += 1;
+    }
+    if count % 3 != 0 {
+        return Err(lines.next_or("the rest of a group of three").unwrap_err());
+    }
+    Ok(String::from(input))
 }
 
 fn item_value(item: char) -> u32 {
@@ -16,7 +34,6 @@ fn item_value(item: char) -> u32 {
     }
 }
 
-#[aoc(day3, part1)]
 pub fn part1(input: &str) -> u32 {
     let mut thesum = 0;
     for line in input.lines() {
@@ -30,7 +47,6 @@ pub fn part1(input: &str) -> u32 {
     thesum
 }
 
-#[aoc(day3, part2)]
 pub fn part2(input: &str) -> u32 {
     let mut thesum = 0;
     for lines in input.lines().collect::<Vec<_>>().chunks(3) {
@@ -38,28 +54,61 @@ pub fn part2(input: &str) -> u32 {
         let bag2: HashSet<char> = lines[1].chars().collect();
         let bag3: HashSet<char> = lines[2].chars().collect();
         let items: HashSet<_> = bag1.intersection(&bag2).copied().collect();
-        let items: Vec<_> = items.intersection(&bag3).collect();
-        thesum += item_value(*items[0]);
+        for item in items.intersection(&bag3) {
+            thesum += item_value(*item);
+        }
     }
     thesum
 }
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = String;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/03a.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(3, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 157);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/03a.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(3, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 70);
     }
+
+    #[test]
+    fn test_load_input() {
+        let err = load_input("abAB\nab1B\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        let err = load_input("abAB\nabc\n").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 4));
+        assert_eq!(load_input("abAB\n\n").unwrap_err().line, 2);
+        let err = load_input("abAB\nabAB\n").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert!(load_input("ab\nab\nab\n").is_ok());
+    }
 }
@@ -1,12 +1,14 @@
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
 use scan_fmt::scan_fmt;
 
-#[aoc_generator(day4)]
-pub fn load_input(input: &str) -> Vec<(i32, i32, i32, i32)> {
+pub fn load_input(input: &str) -> Result<Vec<(i32, i32, i32, i32)>, ParseError> {
     let mut output = vec![];
-    for line in input.lines() {
-        output.push(scan_fmt!(line, "{}-{},{}-{}", i32, i32, i32, i32).unwrap())
+    for line in Lines::new(4, input) {
+        let ranges = scan_fmt!(line.text, "{}-{},{}-{}", i32, i32, i32, i32);
+        output.push(ranges.map_err(|_| line.scan_error("{}-{},{}-{}"))?)
     }
-    output
+    Ok(output)
 }
 
 fn contain_other(e1min: i32, e1max: i32, e2min: i32, e2max: i32) -> bool {
@@ -17,7 +19,6 @@ fn overlap_at_all(e1min: i32, e1max: i32, e2min: i32, e2max: i32) -> bool {
     !(e1max < e2min || e2max < e1min)
 }
 
-#[aoc(day4, part1)]
 pub fn part1(input: &[(i32, i32, i32, i32)]) -> usize {
     input
         .iter()
@@ -25,7 +26,6 @@ pub fn part1(input: &[(i32, i32, i32, i32)]) -> usize {
         .count()
 }
 
-#[aoc(day4, part2)]
 pub fn part2(input: &[(i32, i32, i32, i32)]) -> usize {
     input
         .iter()
@@ -33,22 +33,42 @@ pub fn part2(input: &[(i32, i32, i32, i32)]) -> usize {
         .count()
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<(i32, i32, i32, i32)>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/04a.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(4, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 2);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/04a.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(4, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 4);
     }
 }
@@ -1,21 +1,22 @@
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
 use scan_fmt::scan_fmt;
 
-struct Input {
+pub struct Input {
     setup: Vec<Vec<char>>,
     instructions: Vec<(usize, usize, usize)>,
 }
 
-#[aoc_generator(day5)]
-fn load_input(input: &str) -> Input {
+fn load_input(input: &str) -> Result<Input, ParseError> {
     let mut start = true;
     let mut instructions = vec![];
     let mut start_end_idx = 0;
-    for (i, line) in input.lines().enumerate() {
+    for (i, line) in Lines::new(5, input).enumerate() {
         if !start {
-            instructions
-                .push(scan_fmt!(line, "move {} from {} to {}", usize, usize, usize).unwrap())
+            let inst = scan_fmt!(line.text, "move {} from {} to {}", usize, usize, usize);
+            instructions.push(inst.map_err(|_| line.scan_error("move {} from {} to {}"))?);
         }
-        if line.is_empty() {
+        if line.text.is_empty() {
             start = false;
             start_end_idx = i;
         }
@@ -24,21 +25,24 @@ fn load_input(input: &str) -> Input {
     let peek: String = input.lines().take(1).collect();
     let n_cols = (peek.len() + 1) / 4;
     let mut setup: Vec<Vec<char>> = vec![vec![]; n_cols];
-    let start_lines: Vec<_> = input.lines().take(start_end_idx).collect();
+    let start_lines: Vec<_> = Lines::new(5, input).take(start_end_idx).collect();
     for line in start_lines.into_iter().rev() {
-        for (i, c) in line.chars().skip(1).step_by(4).enumerate() {
+        for (i, c) in line.text.chars().skip(1).step_by(4).enumerate() {
             if c != ' ' {
-                setup[i].push(c);
+                let expected = format!("at most {} stacks", n_cols);
+                let stack = setup
+                    .get_mut(i)
+                    .ok_or_else(|| line.error_at(4 * i + 2, &expected))?;
+                stack.push(c);
             }
         }
     }
-    Input {
+    Ok(Input {
         setup,
         instructions,
-    }
+    })
 }
 
-#[aoc(day5, part1)]
 fn part1(input: &Input) -> String {
     let mut setup = input.setup.clone();
     for (n_move, from, to) in &input.instructions {
@@ -55,7 +59,6 @@ fn part1(input: &Input) -> String {
     output
 }
 
-#[aoc(day5, part2)]
 fn part2(input: &Input) -> String {
     let mut setup = input.setup.clone();
     for (n_move, from, to) in &input.instructions {
@@ -71,22 +74,42 @@ fn part2(input: &Input) -> String {
     output
 }
 
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Input;
+    type Answer1 = String;
+    type Answer2 = String;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/05.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(5, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), String::from("CMZ"));
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/05.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(5, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), String::from("MCD"));
     }
 }
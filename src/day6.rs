@@ -1,3 +1,5 @@
+use crate::parse::ParseError;
+use crate::solution::Solution;
 use std::collections::HashSet;
 
 fn solve(input: &str, l: usize) -> usize {
@@ -9,18 +11,36 @@ fn solve(input: &str, l: usize) -> usize {
     unreachable!();
 }
 
-#[aoc(day6, part1)]
 fn part1(input: &str) -> usize {
     let l = 4;
     solve(input, l)
 }
 
-#[aoc(day6, part2)]
 fn part2(input: &str) -> usize {
     let l = 14;
     solve(input, l)
 }
 
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = String;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(input.trim_end().to_string())
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
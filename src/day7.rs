@@ -1,7 +1,9 @@
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
 use std::collections::HashMap;
 
 #[derive(Clone, Debug)]
-struct Directory {
+pub struct Directory {
     dirs: Vec<String>,
     files: Vec<usize>,
     size: usize,
@@ -28,25 +30,29 @@ impl Directory {
     }
 }
 
-#[aoc_generator(day7)]
-fn load_input(input: &str) -> HashMap<String, Directory> {
+fn load_input(input: &str) -> Result<HashMap<String, Directory>, ParseError> {
     let mut dirs: HashMap<String, Directory> = HashMap::new();
     let mut cwd = String::from("ROOT");
-    for line in input.lines() {
-        let words: Vec<_> = line.split(' ').collect();
+    for line in Lines::new(7, input) {
+        let words: Vec<_> = line.text.split(' ').collect();
+        let word = |i: usize, expected: &str| {
+            let missing = line.error_at(line.text.chars().count() + 2, expected);
+            words.get(i).copied().ok_or(missing)
+        };
         if words[0] == "$" {
             // Command
-            match words[1] {
+            match word(1, "a command")? {
                 "cd" => {
-                    if words[2] == "/" {
+                    let dir = word(2, "a directory")?;
+                    if dir == "/" {
                         cwd = String::from("ROOT");
-                    } else if words[2] == ".." {
+                    } else if dir == ".." {
                         let mut dirlist: Vec<_> = cwd.split('/').collect();
                         dirlist.pop();
                         cwd = dirlist.join("/").to_string();
                     } else {
                         cwd.push('/');
-                        cwd.push_str(words[2]);
+                        cwd.push_str(dir);
                     }
                 }
                 "ls" => {
@@ -65,26 +71,27 @@ fn load_input(input: &str) -> HashMap<String, Directory> {
             // Response
             match words[0] {
                 "dir" => {
+                    let name = word(1, "a directory name")?;
                     if let Some(d) = dirs.get_mut(&cwd) {
                         let mut temp = cwd.clone();
                         temp.push('/');
-                        temp.push_str(words[1]);
+                        temp.push_str(name);
                         d.dirs.push(temp);
                     }
                 }
                 _ => {
                     // File
+                    let size = line.parse::<usize>(words[0], "a file size or `dir`")?;
                     if let Some(d) = dirs.get_mut(&cwd) {
-                        d.files.push(words[0].parse::<usize>().unwrap());
+                        d.files.push(size);
                     }
                 }
             }
         }
     }
-    dirs
+    Ok(dirs)
 }
 
-#[aoc(day7, part1)]
 fn part1(input: &HashMap<String, Directory>) -> usize {
     let mut _input = (*input).clone();
     let mut thesum = 0;
@@ -101,7 +108,6 @@ fn part1(input: &HashMap<String, Directory>) -> usize {
     thesum
 }
 
-#[aoc(day7, part2)]
 fn part2(input: &HashMap<String, Directory>) -> usize {
     let total_size = 70_000_000;
     let need = 30_000_000;
@@ -128,20 +134,40 @@ fn part2(input: &HashMap<String, Directory>) -> usize {
     choices[0].0
 }
 
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = HashMap<String, Directory>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = load_input(&read_to_string("input/2022/07.txt").unwrap());
+        let input = load_input(&load(7, &InputKind::Example(0)).unwrap()).unwrap();
         assert_eq!(part1(&input), 95437);
     }
 
     #[test]
     fn test_part2() {
-        let input = load_input(&read_to_string("input/2022/07.txt").unwrap());
+        let input = load_input(&load(7, &InputKind::Example(0)).unwrap()).unwrap();
         assert_eq!(part2(&input), 24933642);
     }
 }
@@ -1,149 +1,84 @@
-#![allow(clippy::needless_range_loop)]
-#[aoc_generator(day8)]
-fn load_input(input: &str) -> Vec<Vec<u32>> {
-    let mut output = vec![];
-    for line in input.lines() {
-        let mut temp = vec![];
-        for c in line.chars() {
-            temp.push(c.to_digit(10).unwrap());
-        }
-        output.push(temp);
-    }
-    output
-}
-
-#[aoc(day8, part1)]
-fn part1(input: &Vec<Vec<u32>>) -> u32 {
-    let nrows = input.len();
-    let ncols = input[0].len();
+use crate::geom::{Direction4, Point2};
+use crate::grid::Grid;
+use crate::parse::ParseError;
+use crate::solution::Solution;
 
-    let mut is_visible: Vec<Vec<u32>> = vec![vec![0; ncols]; nrows];
-    is_visible[0][0] = 1;
-    is_visible[0][ncols - 1] = 1;
-    is_visible[nrows - 1][0] = 1;
-    is_visible[nrows - 1][ncols - 1] = 1;
+fn load_input(input: &str) -> Result<Grid<u32>, ParseError> {
+    Grid::from_str(8, input, "a digit", |c| c.to_digit(10))
+}
 
-    for x in 1..nrows - 1 {
-        // look from left
-        let mut row_max = input[x][0];
-        is_visible[x][0] = 1;
-        for y in 1..ncols - 1 {
-            if input[x][y] > row_max {
-                row_max = input[x][y];
-                is_visible[x][y] = 1;
-            }
-            if row_max == 9 {
-                break;
-            }
-        }
+fn part1(input: &Grid<u32>) -> usize {
+    // A tree is visible if everything between it and some edge is shorter
+    input
+        .iter()
+        .filter(|&(p, &height)| {
+            Direction4::ALL
+                .iter()
+                .any(|&dir| input.ray(p, dir).all(|q| input[q] < height))
+        })
+        .count()
+}
 
-        // look from right
-        let mut row_max = input[x][ncols - 1];
-        is_visible[x][ncols - 1] = 1;
-        for y in (1..ncols - 1).rev() {
-            if input[x][y] > row_max {
-                row_max = input[x][y];
-                is_visible[x][y] = 1;
-            }
-            if row_max == 9 {
-                break;
-            }
+/// Trees seen looking from `p` in `dir`, up to and including the first one
+/// at least as tall.
+fn viewing_distance(input: &Grid<u32>, p: Point2<usize>, dir: Direction4) -> u32 {
+    let mut seen = 0;
+    for q in input.ray(p, dir) {
+        seen += 1;
+        if input[q] >= input[p] {
+            break;
         }
     }
+    seen
+}
 
-    for y in 1..ncols - 1 {
-        // look from top
-        let mut col_max = input[0][y];
-        is_visible[0][y] = 1;
-        for x in 1..nrows - 1 {
-            if input[x][y] > col_max {
-                col_max = input[x][y];
-                is_visible[x][y] = 1;
-            }
-            if col_max == 9 {
-                break;
-            }
-        }
-
-        // look from bottom
-        let mut col_max = input[nrows - 1][y];
-        is_visible[nrows - 1][y] = 1;
-        for x in (1..nrows - 1).rev() {
-            if input[x][y] > col_max {
-                col_max = input[x][y];
-                is_visible[x][y] = 1;
-            }
-            if col_max == 9 {
-                break;
-            }
-        }
-    }
-    is_visible.iter().flatten().sum::<u32>()
+fn part2(input: &Grid<u32>) -> u32 {
+    input
+        .points()
+        .map(|p| {
+            Direction4::ALL
+                .iter()
+                .map(|&dir| viewing_distance(input, p, dir))
+                .product()
+        })
+        .max()
+        .unwrap()
 }
 
-#[aoc(day8, part2)]
-fn part2(input: &Vec<Vec<u32>>) -> u32 {
-    let nrows = input.len();
-    let ncols = input[0].len();
+pub struct Day8;
 
-    let mut score: Vec<Vec<u32>> = vec![vec![1; ncols]; nrows];
+impl Solution for Day8 {
+    type Input = Grid<u32>;
+    type Answer1 = usize;
+    type Answer2 = u32;
 
-    for x in 1..nrows - 1 {
-        for y in 1..ncols - 1 {
-            let height = input[x][y];
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
 
-            // look left
-            let mut left_score = 0;
-            for yy in (0..y).rev() {
-                left_score += 1;
-                if input[x][yy] >= height {
-                    break;
-                }
-            }
-            // look right
-            let mut right_score = 0;
-            for yy in y + 1..ncols {
-                right_score += 1;
-                if input[x][yy] >= height {
-                    break;
-                }
-            }
-            // look up
-            let mut up_score = 0;
-            for xx in (0..x).rev() {
-                up_score += 1;
-                if input[xx][y] >= height {
-                    break;
-                }
-            }
-            // look down
-            let mut down_score = 0;
-            for xx in x + 1..nrows {
-                down_score += 1;
-                if input[xx][y] >= height {
-                    break;
-                }
-            }
-            score[x][y] = left_score * right_score * up_score * down_score;
-        }
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
     }
-    *score.iter().flatten().max().unwrap()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = load_input(&read_to_string("input/2022/08.txt").unwrap());
+        let input = load_input(&load(8, &InputKind::Example(0)).unwrap()).unwrap();
         assert_eq!(part1(&input), 21);
     }
 
     #[test]
     fn test_part2() {
-        let input = load_input(&read_to_string("input/2022/08.txt").unwrap());
+        let input = load_input(&load(8, &InputKind::Example(0)).unwrap()).unwrap();
         assert_eq!(part2(&input), 8);
     }
 }
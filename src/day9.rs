@@ -1,146 +1,99 @@
-#![allow(clippy::comparison_chain)]
+use crate::geom::{Direction4, Point2};
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
 use scan_fmt::scan_fmt;
 use std::collections::HashSet;
 
-type Input = Vec<(char, usize)>;
+type Input = Vec<(Direction4, usize)>;
 
-#[aoc_generator(day9)]
-fn load_input(input: &str) -> Input {
+fn load_input(input: &str) -> Result<Input, ParseError> {
     let mut output = vec![];
-    for line in input.lines() {
-        let (dir, n) = scan_fmt!(line, "{} {}", char, usize).unwrap();
+    for line in Lines::new(9, input) {
+        let (dir, n) =
+            scan_fmt!(line.text, "{} {}", char, usize).map_err(|_| line.scan_error("{} {}"))?;
+        let dir = match dir {
+            'U' => Direction4::North,
+            'D' => Direction4::South,
+            'L' => Direction4::West,
+            'R' => Direction4::East,
+            _ => return Err(line.error_at(1, "`U`, `D`, `L` or `R`")),
+        };
         output.push((dir, n));
     }
-    output
+    Ok(output)
 }
 
-fn move_towards(tail: (i32, i32), head: (i32, i32)) -> (i32, i32) {
-    let xdist = (tail.0 - head.0).abs();
-    let ydist = (tail.1 - head.1).abs();
-    let mdist = xdist + ydist;
-    let mut m = (0, 0);
-    if mdist > 2 {
-        // diag
-        if tail.0 - head.0 > 0 {
-            m.0 = -1;
-        } else if tail.0 - head.0 < 0 {
-            m.0 = 1;
-        }
-        if tail.1 - head.1 > 0 {
-            m.1 = -1;
-        } else if tail.1 - head.1 < 0 {
-            m.1 = 1;
-        }
-    } else {
-        if tail.0 - head.0 > 1 {
-            m.0 = -1;
-        } else if tail.0 - head.0 < -1 {
-            m.0 = 1;
-        }
-        if tail.1 - head.1 > 1 {
-            m.1 = -1;
-        } else if tail.1 - head.1 < -1 {
-            m.1 = 1;
-        }
+/// Where the tail ends up once the head has moved. It only follows when
+/// the two stop touching, and then moves at most one step on each axis.
+fn move_towards(tail: Point2<i32>, head: Point2<i32>) -> Point2<i32> {
+    if tail.chebyshev(head) <= 1 {
+        return tail;
     }
-
-    (tail.0 + m.0, tail.1 + m.1)
+    let diff = head - tail;
+    tail + Point2::new(diff.x.signum(), diff.y.signum())
 }
 
-#[aoc(day9, part1)]
-fn part1(input: &Input) -> usize {
-    let mut tail_locs: HashSet<(i32, i32)> = HashSet::new();
-    let mut head: (i32, i32) = (0, 0);
-    let mut tail: (i32, i32) = (0, 0);
-    tail_locs.insert(tail);
-    for line in input {
-        for _ in 0..line.1 {
-            match line.0 {
-                'R' => {
-                    head.1 += 1;
-                    tail = move_towards(tail, head);
-                }
-                'D' => {
-                    head.0 -= 1;
-                    tail = move_towards(tail, head);
-                }
-                'L' => {
-                    head.1 -= 1;
-                    tail = move_towards(tail, head);
-                }
-                'U' => {
-                    head.0 += 1;
-                    tail = move_towards(tail, head);
-                }
-                _ => (),
+/// Number of places the last knot of a rope `knots` long visits.
+fn simulate(input: &Input, knots: usize) -> usize {
+    let mut rope = vec![Point2::default(); knots];
+    let mut tail_locs: HashSet<Point2<i32>> = HashSet::new();
+    tail_locs.insert(rope[knots - 1]);
+    for &(dir, n) in input {
+        for _ in 0..n {
+            rope[0] = rope[0].step(dir);
+            for i in 1..knots {
+                rope[i] = move_towards(rope[i], rope[i - 1]);
             }
-            tail_locs.insert(tail);
+            tail_locs.insert(rope[knots - 1]);
         }
     }
     tail_locs.len()
 }
 
-#[aoc(day9, part2)]
+fn part1(input: &Input) -> usize {
+    simulate(input, 2)
+}
+
 fn part2(input: &Input) -> usize {
-    let mut tail_locs: HashSet<(i32, i32)> = HashSet::new();
-    let mut head: (i32, i32) = (0, 0);
-    let mut tails: Vec<(i32, i32)> = vec![(0, 0); 9];
-    tail_locs.insert(head);
-    for line in input {
-        for _ in 0..line.1 {
-            match line.0 {
-                'R' => {
-                    head.1 += 1;
-                    tails[0] = move_towards(tails[0], head);
-                    for i in 1..9 {
-                        tails[i] = move_towards(tails[i], tails[i - 1]);
-                    }
-                }
-                'D' => {
-                    head.0 -= 1;
-                    tails[0] = move_towards(tails[0], head);
-                    for i in 1..9 {
-                        tails[i] = move_towards(tails[i], tails[i - 1]);
-                    }
-                }
-                'L' => {
-                    head.1 -= 1;
-                    tails[0] = move_towards(tails[0], head);
-                    for i in 1..9 {
-                        tails[i] = move_towards(tails[i], tails[i - 1]);
-                    }
-                }
-                'U' => {
-                    head.0 += 1;
-                    tails[0] = move_towards(tails[0], head);
-                    for i in 1..9 {
-                        tails[i] = move_towards(tails[i], tails[i - 1]);
-                    }
-                }
-                _ => (),
-            }
-            tail_locs.insert(tails[8]);
-        }
+    simulate(input, 10)
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
     }
-    tail_locs.len()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = load_input(&read_to_string("input/2022/09.txt").unwrap());
+        let input = load_input(&load(9, &InputKind::Example(0)).unwrap()).unwrap();
         assert_eq!(part1(&input), 13);
     }
 
     #[test]
     fn test_part2() {
-        let input = load_input(&read_to_string("input/2022/09.txt").unwrap());
+        let input = load_input(&load(9, &InputKind::Example(0)).unwrap()).unwrap();
         assert_eq!(part2(&input), 1);
-        let input = load_input(&read_to_string("input/2022/09a.txt").unwrap());
+        let input = load_input(&load(9, &InputKind::Example(1)).unwrap()).unwrap();
         assert_eq!(part2(&input), 36);
     }
 }
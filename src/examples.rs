@@ -0,0 +1,149 @@
+use crate::answers::{AnswersError, KnownAnswer};
+use crate::inputs::{example_paths, load_path, InputKind};
+use crate::solution::Day;
+use serde::Deserialize;
+use std::fs::read_to_string;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Expected answers to one example, kept beside it with the same name:
+/// `10.toml` answers `10.txt`. A part can be left out if its answer isn't
+/// known, or the day only solves the example with different parameters.
+///
+/// ```toml
+/// part1 = 13140
+/// part2 = """
+/// ##..##..##..##..##..##..##..##..##..##..
+/// ...
+/// """
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Expected {
+    pub part1: Option<toml::Value>,
+    pub part2: Option<toml::Value>,
+}
+
+impl Expected {
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// The answer to `part` as a known answer, so it's checked the same way
+    /// as the ones in `answers.toml`. Multi-line answers are written with a
+    /// final newline in the file, which isn't part of the answer.
+    pub fn part(&self, part: u8) -> Option<KnownAnswer> {
+        let value = match part {
+            1 => self.part1.as_ref()?,
+            2 => self.part2.as_ref()?,
+            _ => return None,
+        };
+        let value = match value {
+            toml::Value::String(s) => toml::Value::String(s.trim_end_matches('\n').to_string()),
+            other => other.clone(),
+        };
+        Some(KnownAnswer {
+            answer: Some(value),
+            ..KnownAnswer::default()
+        })
+    }
+}
+
+/// Where the expected answers to the example at `path` are kept.
+pub fn sidecar_path(example: &Path) -> PathBuf {
+    example.with_extension("toml")
+}
+
+/// The expected answers to the example at `path`, or None if it has none.
+pub fn load_expected(example: &Path) -> Result<Option<Expected>, AnswersError> {
+    let path = sidecar_path(example);
+    match read_to_string(&path) {
+        Ok(text) => Expected::parse(&text)
+            .map(Some)
+            .map_err(|e| AnswersError::Toml(path, e)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(AnswersError::Io(path, e)),
+    }
+}
+
+/// Every example of `day` that has expected answers, by example number.
+pub fn expected_answers(day: u32) -> Result<Vec<(InputKind, Expected)>, AnswersError> {
+    let mut output = vec![];
+    for (n, path) in example_paths(day).iter().enumerate() {
+        if let Some(expected) = load_expected(path)? {
+            output.push((InputKind::Example(n), expected));
+        }
+    }
+    Ok(output)
+}
+
+/// Run `part` of `day` on each of its examples with an expected answer.
+/// Returns how many were checked, or what went wrong with each that failed.
+pub fn check_examples(day: &Day, part: u8) -> Result<usize, Vec<String>> {
+    if !day.has_part(part) {
+        return Ok(0);
+    }
+    let expected = expected_answers(day.day).map_err(|e| vec![e.to_string()])?;
+    let paths = example_paths(day.day);
+    let mut checked = 0;
+    let mut failures = vec![];
+    for (kind, expected) in expected {
+        let Some(known) = expected.part(part) else {
+            continue;
+        };
+        let InputKind::Example(n) = kind else {
+            unreachable!("sidecars only belong to examples");
+        };
+        let input = load_path(&paths[n]).map_err(|e| vec![e.to_string()])?;
+        checked += 1;
+        match day.run(part, &input) {
+            Ok(Some(answer)) => {
+                let verdict = known.check(&answer);
+                if verdict.is_wrong() {
+                    failures.push(format!("{}: got\n{}\n{}", kind, answer, verdict));
+                }
+            }
+            Ok(None) => unreachable!("part {} is registered", part),
+            Err(e) => failures.push(format!("{}: {}", kind, e.diagnostic())),
+        }
+    }
+    if failures.is_empty() {
+        Ok(checked)
+    } else {
+        Err(failures)
+    }
+}
+
+/// Panic with every failure if `part` of `day` gets any of its examples
+/// wrong.
+pub fn assert_examples(day: u32, part: u8) {
+    let day = crate::get_day(day).expect("day is registered");
+    if let Err(failures) = check_examples(day, part) {
+        panic!(
+            "day {} part {} failed its examples:\n{}",
+            day.day,
+            part,
+            failures.join("\n")
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::answers::Verdict;
+
+    #[test]
+    fn test_expected() {
+        let expected = Expected::parse("part1 = 24000\npart2 = \"\"\"\n#.\n.#\n\"\"\"\n").unwrap();
+        assert_eq!(expected.part(1).unwrap().check("24000"), Verdict::Correct);
+        assert_eq!(expected.part(2).unwrap().check("#.\n.#"), Verdict::Correct);
+        assert!(expected.part(2).unwrap().check("#.\n##").is_wrong());
+        assert!(Expected::parse("part3 = 1").is_err());
+
+        let sidecars = expected_answers(9).unwrap();
+        assert_eq!(sidecars.len(), 2);
+        assert_eq!(sidecars[1].0, InputKind::Example(1));
+        assert_eq!(sidecars[1].1.part1, None);
+    }
+}
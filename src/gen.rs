@@ -0,0 +1,914 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::{self, Write as _};
+
+/// Small seeded random number generator (SplitMix64). Kept in house so a
+/// seed gives the same input on every machine and every version of our
+/// dependencies, which is what makes generated inputs shareable.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `lo..=hi`.
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        assert!(lo <= hi, "empty range {}..={}", lo, hi);
+        let span = (hi - lo) as u128 + 1;
+        lo + ((self.next_u64() as u128 * span) >> 64) as i64
+    }
+
+    /// Uniform in `0..n`.
+    pub fn below(&mut self, n: usize) -> usize {
+        self.range(0, n as i64 - 1) as usize
+    }
+
+    /// True with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+
+    /// A word of `len` random letters from `alphabet`.
+    fn word(&mut self, alphabet: &[char], len: usize) -> String {
+        (0..len).map(|_| *self.pick(alphabet)).collect()
+    }
+}
+
+const LOWER: [char; 26] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+fn upper() -> Vec<char> {
+    LOWER.iter().map(|c| c.to_ascii_uppercase()).collect()
+}
+
+/// How to make puzzle input for one day.
+pub struct Generator {
+    pub day: u32,
+    /// What the size counts, for help text.
+    pub size: &'static str,
+    /// A size close to the real inputs.
+    pub default_size: usize,
+    make: fn(&mut Rng, usize) -> String,
+}
+
+impl Generator {
+    const fn new(
+        day: u32,
+        size: &'static str,
+        default_size: usize,
+        make: fn(&mut Rng, usize) -> String,
+    ) -> Self {
+        Self {
+            day,
+            size,
+            default_size,
+            make,
+        }
+    }
+
+    /// Input of roughly `size`, the same every time for the same `seed`.
+    pub fn generate(&self, size: usize, seed: u64) -> String {
+        (self.make)(&mut Rng::new(seed), size.max(1))
+    }
+}
+
+pub static GENERATORS: &[Generator] = &[
+    Generator::new(1, "elves", 250, day1),
+    Generator::new(2, "rounds", 2500, day2),
+    Generator::new(3, "groups of three elves", 100, day3),
+    Generator::new(4, "pairs", 1000, day4),
+    Generator::new(5, "moves", 500, day5),
+    Generator::new(6, "characters", 4000, day6),
+    Generator::new(7, "directories", 200, day7),
+    Generator::new(8, "trees across", 99, day8),
+    Generator::new(9, "moves", 2000, day9),
+    Generator::new(10, "cycles", 240, day10),
+    Generator::new(11, "monkeys", 8, day11),
+    Generator::new(12, "squares across", 160, day12),
+    Generator::new(13, "pairs", 150, day13),
+    Generator::new(14, "rock paths", 150, day14),
+    Generator::new(15, "sensors", 30, day15),
+    Generator::new(16, "valves", 60, day16),
+    Generator::new(17, "jets", 10000, day17),
+    Generator::new(18, "cubes", 2800, day18),
+    Generator::new(19, "blueprints", 30, day19),
+    Generator::new(20, "numbers", 5000, day20),
+    Generator::new(21, "monkeys", 2000, day21),
+    Generator::new(22, "squares along a cube edge", 50, day22),
+    Generator::new(23, "squares across", 70, day23),
+];
+
+pub fn get_generator(day: u32) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|g| g.day == day)
+}
+
+/// Calorie counts, one group per elf.
+fn day1(rng: &mut Rng, elves: usize) -> String {
+    let groups: Vec<String> = (0..elves)
+        .map(|_| {
+            let items: Vec<String> = (0..rng.range(1, 15))
+                .map(|_| rng.range(1000, 60000).to_string())
+                .collect();
+            items.join("\n")
+        })
+        .collect();
+    groups.join("\n\n") + "\n"
+}
+
+fn day2(rng: &mut Rng, rounds: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..rounds {
+        let them = rng.pick(&['A', 'B', 'C']);
+        let us = rng.pick(&['X', 'Y', 'Z']);
+        writeln!(output, "{} {}", them, us).unwrap();
+    }
+    output
+}
+
+/// Rucksacks whose halves share exactly one item type, in groups of three
+/// sharing exactly one badge.
+fn day3(rng: &mut Rng, groups: usize) -> String {
+    let mut letters: Vec<char> = LOWER.iter().copied().chain(upper()).collect();
+    let mut output = String::new();
+    for _ in 0..groups {
+        rng.shuffle(&mut letters);
+        let badge = letters[0];
+        // Each elf draws from its own 15 letters, so only the badge is
+        // common to all three
+        for pool in letters[1..46].chunks(15) {
+            let (shared, left, right) = (pool[0], &pool[1..8], &pool[8..]);
+            let half = rng.range(2, 16) as usize;
+            let mut first = vec![shared, badge];
+            first.extend((2..half).map(|_| *rng.pick(left)));
+            let mut second = vec![shared];
+            second.extend((1..half).map(|_| *rng.pick(right)));
+            rng.shuffle(&mut first);
+            rng.shuffle(&mut second);
+            let line: String = first.into_iter().chain(second).collect();
+            writeln!(output, "{}", line).unwrap();
+        }
+    }
+    output
+}
+
+fn day4(rng: &mut Rng, pairs: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..pairs {
+        let (a, b) = (rng.range(1, 99), rng.range(1, 99));
+        let (c, d) = (rng.range(1, 99), rng.range(1, 99));
+        writeln!(
+            output,
+            "{}-{},{}-{}",
+            a.min(b),
+            a.max(b),
+            c.min(d),
+            c.max(d)
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// Nine stacks and moves that never empty one, so every stack has a crate
+/// on top at the end whichever way the crane works.
+fn day5(rng: &mut Rng, moves: usize) -> String {
+    // With more crates than stacks, some stack always has one to spare
+    let mut stacks: Vec<Vec<char>> = (0..9)
+        .map(|i| {
+            let height = rng.range(if i == 0 { 2 } else { 1 }, 8);
+            (0..height).map(|_| *rng.pick(&upper())).collect()
+        })
+        .collect();
+
+    let mut output = String::new();
+    let tallest = stacks.iter().map(Vec::len).max().unwrap();
+    for level in (0..tallest).rev() {
+        let row: Vec<String> = stacks
+            .iter()
+            .map(|stack| match stack.get(level) {
+                Some(c) => format!("[{}]", c),
+                None => String::from("   "),
+            })
+            .collect();
+        writeln!(output, "{}", row.join(" ")).unwrap();
+    }
+    let labels: Vec<String> = (1..=9).map(|i| format!(" {} ", i)).collect();
+    writeln!(output, "{}\n", labels.join(" ")).unwrap();
+
+    for _ in 0..moves {
+        let from = loop {
+            let i = rng.below(9);
+            if stacks[i].len() > 1 {
+                break i;
+            }
+        };
+        let to = (from + rng.range(1, 8) as usize) % 9;
+        let n = rng.range(1, stacks[from].len() as i64 - 1) as usize;
+        let at = stacks[from].len() - n;
+        let moved: Vec<char> = stacks[from].drain(at..).collect();
+        stacks[to].extend(moved);
+        writeln!(output, "move {} from {} to {}", n, from + 1, to + 1).unwrap();
+    }
+    output
+}
+
+/// A stream where four different characters in a row turn up early, but
+/// fourteen only right at the end.
+fn day6(rng: &mut Rng, len: usize) -> String {
+    let mut letters = LOWER.to_vec();
+    rng.shuffle(&mut letters);
+    let mut output = rng.word(&letters[..8], len.saturating_sub(14));
+    rng.shuffle(&mut letters);
+    output.extend(&letters[..14]);
+    output + "\n"
+}
+
+/// One directory of a generated file system.
+struct Dir {
+    name: String,
+    children: Vec<usize>,
+    files: Vec<(String, i64)>,
+}
+
+/// `$ ls` in `dir`, then the same for each directory inside it.
+fn explore(tree: &[Dir], dir: usize, output: &mut String) {
+    output.push_str("$ ls\n");
+    for &child in &tree[dir].children {
+        writeln!(output, "dir {}", tree[child].name).unwrap();
+    }
+    for (name, size) in &tree[dir].files {
+        writeln!(output, "{} {}", size, name).unwrap();
+    }
+    for &child in &tree[dir].children {
+        writeln!(output, "$ cd {}", tree[child].name).unwrap();
+        explore(tree, child, output);
+        output.push_str("$ cd ..\n");
+    }
+}
+
+/// A terminal session exploring a random directory tree, using between 40
+/// and 70 million of the 70 million bytes so there is always something
+/// worth deleting.
+fn day7(rng: &mut Rng, dirs: usize) -> String {
+    let mut tree = vec![Dir {
+        name: String::from("/"),
+        children: vec![],
+        files: vec![],
+    }];
+    for i in 1..dirs {
+        let parent = rng.below(i);
+        let len = rng.range(1, 6) as usize;
+        tree[parent].children.push(i);
+        tree.push(Dir {
+            // Numbered so no two in one directory have the same name
+            name: format!("{}{}", rng.word(&LOWER, len), i),
+            children: vec![],
+            files: vec![],
+        });
+    }
+
+    let nfiles: Vec<usize> = (0..dirs).map(|_| rng.range(0, 4) as usize).collect();
+    let total: usize = nfiles.iter().sum();
+    let mut left = rng.range(45_000_000, 65_000_000);
+    let most = (2 * left / (total as i64 + 1)).max(2);
+    for (dir, &n) in nfiles.iter().enumerate() {
+        for _ in 0..n {
+            let mut size = rng.range(1, most);
+            if size >= left {
+                size = 1;
+            }
+            left -= size;
+            let name = format!("{}.{}", rng.word(&LOWER, 5), rng.word(&LOWER, 3));
+            tree[dir].files.push((name, size));
+        }
+    }
+    // Whatever's left over makes up the total
+    tree[0].files.push((String::from("rest"), left.max(1)));
+
+    let mut output = String::from("$ cd /\n");
+    explore(&tree, 0, &mut output);
+    output
+}
+
+fn day8(rng: &mut Rng, side: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..side {
+        let row: String = (0..side)
+            .map(|_| char::from_digit(rng.range(0, 9) as u32, 10).unwrap())
+            .collect();
+        writeln!(output, "{}", row).unwrap();
+    }
+    output
+}
+
+fn day9(rng: &mut Rng, moves: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..moves {
+        writeln!(
+            output,
+            "{} {}",
+            rng.pick(&['U', 'D', 'L', 'R']),
+            rng.range(1, 19)
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// A program running for exactly `cycles` cycles, keeping the sprite on
+/// or near the 40 pixel screen.
+fn day10(rng: &mut Rng, cycles: usize) -> String {
+    let mut output = String::new();
+    let mut x = 1;
+    let mut left = cycles;
+    while left > 0 {
+        if left == 1 || rng.chance(0.3) {
+            output.push_str("noop\n");
+            left -= 1;
+        } else {
+            let v = rng.range((-1 - x).max(-20), (40 - x).min(20));
+            writeln!(output, "addx {}", v).unwrap();
+            x += v;
+            left -= 2;
+        }
+    }
+    output
+}
+
+/// The first `n` primes, for divisibility tests that don't share factors.
+fn primes(n: usize) -> Vec<usize> {
+    let mut output: Vec<usize> = vec![];
+    let mut candidate = 2;
+    while output.len() < n {
+        if output.iter().all(|p| candidate % p != 0) {
+            output.push(candidate);
+        }
+        candidate += 1;
+    }
+    output
+}
+
+struct MonkeySpec {
+    items: Vec<u64>,
+    /// None for squaring, otherwise whether it multiplies and by what.
+    op: Option<(bool, u64)>,
+    divisor: u64,
+    throw: (usize, usize),
+}
+
+/// Whether the 20 rounds of part 1, where nothing keeps worry levels
+/// down but dividing by 3, stay within 64 bits.
+fn part1_fits(monkeys: &[MonkeySpec]) -> bool {
+    let mut items: Vec<Vec<u64>> = monkeys.iter().map(|m| m.items.clone()).collect();
+    for _ in 0..20 {
+        for (i, monkey) in monkeys.iter().enumerate() {
+            for old in std::mem::take(&mut items[i]) {
+                let new = match monkey.op {
+                    None => old.checked_mul(old),
+                    Some((true, n)) => old.checked_mul(n),
+                    Some((false, n)) => old.checked_add(n),
+                };
+                let Some(new) = new.map(|n| n / 3) else {
+                    return false;
+                };
+                let to = if new % monkey.divisor == 0 {
+                    monkey.throw.0
+                } else {
+                    monkey.throw.1
+                };
+                items[to].push(new);
+            }
+        }
+    }
+    true
+}
+
+/// Monkeys testing distinct primes, one of them squaring the worry level
+/// when there are more than two.
+///
+/// Part 2 works modulo the product of the primes, which only fits in 64
+/// bits once squared for up to 9 monkeys.
+fn day11(rng: &mut Rng, monkeys: usize) -> String {
+    let n = monkeys.max(2);
+    let monkeys = loop {
+        let mut divisors = primes(n);
+        rng.shuffle(&mut divisors);
+        // With only two, the other monkey would have to throw to it
+        let squarer = (n > 2).then(|| rng.below(n));
+        let monkeys: Vec<MonkeySpec> = divisors
+            .into_iter()
+            .enumerate()
+            .map(|(i, divisor)| {
+                let items = (0..rng.range(1, 8))
+                    .map(|_| rng.range(50, 99) as u64)
+                    .collect();
+                let op = if Some(i) == squarer {
+                    None
+                } else if rng.chance(0.7) {
+                    Some((false, rng.range(1, 8) as u64))
+                } else {
+                    Some((true, rng.range(2, 7) as u64))
+                };
+                // Nobody throws to the squarer, or worry levels soon
+                // outgrow 64 bits
+                let mut others: Vec<usize> =
+                    (0..n).filter(|&j| j != i && Some(j) != squarer).collect();
+                rng.shuffle(&mut others);
+                MonkeySpec {
+                    items,
+                    op,
+                    divisor: divisor as u64,
+                    throw: (others[0], *others.get(1).unwrap_or(&others[0])),
+                }
+            })
+            .collect();
+        if part1_fits(&monkeys) {
+            break monkeys;
+        }
+    };
+
+    let mut blocks = vec![];
+    for (i, monkey) in monkeys.iter().enumerate() {
+        let items: Vec<String> = monkey.items.iter().map(u64::to_string).collect();
+        let operation = match monkey.op {
+            None => String::from("* old"),
+            Some((true, n)) => format!("* {}", n),
+            Some((false, n)) => format!("+ {}", n),
+        };
+        blocks.push(format!(
+            "Monkey {}:\n  Starting items: {}\n  Operation: new = old {}\n  Test: divisible by {}\n    If true: throw to monkey {}\n    If false: throw to monkey {}\n",
+            i,
+            items.join(", "),
+            operation,
+            monkey.divisor,
+            monkey.throw.0,
+            monkey.throw.1
+        ));
+    }
+    blocks.join("\n")
+}
+
+/// Terrain rising from west to east, with a path along the middle row
+/// that climbs at most one step at a time from `S` to `E`.
+fn day12(rng: &mut Rng, width: usize) -> String {
+    let width = width.max(26);
+    let height = (width / 4).max(5);
+    let middle = height / 2;
+    let mut output = String::new();
+    for y in 0..height {
+        for x in 0..width {
+            let base = (x * 25 / (width - 1)) as i64;
+            let c = match (x, y == middle) {
+                (0, true) => 'S',
+                (x, true) if x == width - 1 => 'E',
+                (_, true) => (b'a' + base as u8) as char,
+                _ => (b'a' + (base - rng.range(0, 3)).max(0) as u8) as char,
+            };
+            output.push(c);
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Packet {
+    Int(i64),
+    List(Vec<Packet>),
+}
+
+impl Packet {
+    fn random(rng: &mut Rng, depth: usize) -> Self {
+        let items = (0..rng.range(0, 4))
+            .map(|_| {
+                if depth < 4 && rng.chance(0.3) {
+                    Packet::random(rng, depth + 1)
+                } else {
+                    Packet::Int(rng.range(0, 10))
+                }
+            })
+            .collect();
+        Packet::List(items)
+    }
+
+    /// The puzzle's order, where an integer compared with a list is treated
+    /// as a list of just that integer.
+    fn order(&self, other: &Packet) -> Ordering {
+        match (self, other) {
+            (Packet::Int(a), Packet::Int(b)) => a.cmp(b),
+            (Packet::List(a), Packet::List(b)) => a
+                .iter()
+                .zip(b)
+                .map(|(x, y)| x.order(y))
+                .find(|o| o.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (Packet::Int(_), Packet::List(_)) => Packet::List(vec![self.clone()]).order(other),
+            (Packet::List(_), Packet::Int(_)) => self.order(&Packet::List(vec![other.clone()])),
+        }
+    }
+}
+
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Packet::Int(n) => write!(f, "{}", n),
+            Packet::List(items) => {
+                let items: Vec<String> = items.iter().map(Packet::to_string).collect();
+                write!(f, "[{}]", items.join(","))
+            }
+        }
+    }
+}
+
+/// Pairs of packets, never two that compare equal since the puzzle
+/// promises one is always in the right order.
+fn day13(rng: &mut Rng, pairs: usize) -> String {
+    let pairs: Vec<String> = (0..pairs)
+        .map(|_| {
+            let left = Packet::random(rng, 0);
+            let right = loop {
+                let right = Packet::random(rng, 0);
+                if left.order(&right).is_ne() {
+                    break right;
+                }
+            };
+            format!("{}\n{}\n", left, right)
+        })
+        .collect();
+    pairs.join("\n")
+}
+
+/// Whether sand poured in at 500,0 eventually falls past every rock,
+/// rather than piling up until it blocks the source.
+fn sand_escapes(rock: &HashSet<(i64, i64)>) -> bool {
+    let lowest = rock.iter().map(|p| p.1).max().unwrap_or(0);
+    let mut filled = rock.clone();
+    while !filled.contains(&(500, 0)) {
+        let (mut x, mut y) = (500, 0);
+        loop {
+            if y > lowest {
+                return true;
+            }
+            match [x, x - 1, x + 1]
+                .into_iter()
+                .find(|&nx| !filled.contains(&(nx, y + 1)))
+            {
+                Some(nx) => (x, y) = (nx, y + 1),
+                None => break,
+            }
+        }
+        filled.insert((x, y));
+    }
+    false
+}
+
+/// Rock paths of straight lines below the sand source, spreading out more
+/// the more of them there are. A path is only kept if sand still finds a
+/// way past the rocks, as part 1 needs.
+fn day14(rng: &mut Rng, paths: usize) -> String {
+    let spread = 10 + paths as i64 / 2;
+    let mut rock = HashSet::new();
+    let mut output = String::new();
+    let mut kept = 0;
+    for _ in 0..paths * 10 {
+        if kept == paths {
+            break;
+        }
+        let mut x = rng.range(500 - spread, 500 + spread);
+        let mut y = rng.range(13, 13 + spread);
+        let mut points = vec![(x, y)];
+        for i in 0..rng.range(1, 5) {
+            let step = rng.range(1, 8) * if rng.chance(0.5) { 1 } else { -1 };
+            if i % 2 == 0 {
+                x += step;
+            } else {
+                y = (y + step).max(1);
+            }
+            points.push((x, y));
+        }
+
+        let mut with_path = rock.clone();
+        for ends in points.windows(2) {
+            let ((x0, y0), (x1, y1)) = (ends[0], ends[1]);
+            for x in x0.min(x1)..=x0.max(x1) {
+                for y in y0.min(y1)..=y0.max(y1) {
+                    with_path.insert((x, y));
+                }
+            }
+        }
+        if !sand_escapes(&with_path) {
+            continue;
+        }
+        rock = with_path;
+        kept += 1;
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{},{}", x, y)).collect();
+        writeln!(output, "{}", points.join(" -> ")).unwrap();
+    }
+    output
+}
+
+/// Sensors over the 4000000 square that part 2 searches. Nothing arranges
+/// for exactly one gap in their coverage, so only part 1 has a well defined
+/// answer.
+fn day15(rng: &mut Rng, sensors: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..sensors {
+        let (x, y) = (rng.range(0, 4_000_000), rng.range(0, 4_000_000));
+        let reach = rng.range(100_000, 1_000_000);
+        let dx = rng.range(-reach, reach);
+        let dy = (reach - dx.abs()) * if rng.chance(0.5) { 1 } else { -1 };
+        writeln!(
+            output,
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            x,
+            y,
+            x + dx,
+            y + dy
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// A connected tunnel network from `AA`, with at most 15 valves worth
+/// opening like the real inputs.
+fn day16(rng: &mut Rng, valves: usize) -> String {
+    let valves = valves.clamp(2, 26 * 26);
+    let letters = upper();
+    let mut names = vec![String::from("AA")];
+    let mut seen: HashSet<String> = names.iter().cloned().collect();
+    while names.len() < valves {
+        let name = rng.word(&letters, 2);
+        if seen.insert(name.clone()) {
+            names.push(name);
+        }
+    }
+
+    let mut tunnels: Vec<Vec<usize>> = vec![vec![]; valves];
+    let mut connect = |a: usize, b: usize| {
+        if a != b && !tunnels[a].contains(&b) {
+            tunnels[a].push(b);
+            tunnels[b].push(a);
+        }
+    };
+    for i in 1..valves {
+        connect(i, rng.below(i));
+    }
+    for _ in 0..valves / 4 {
+        connect(rng.below(valves), rng.below(valves));
+    }
+
+    let mut rates = vec![0; valves];
+    let mut order: Vec<usize> = (1..valves).collect();
+    rng.shuffle(&mut order);
+    for &v in order.iter().take((valves / 3).clamp(1, 15)) {
+        rates[v] = rng.range(3, 25);
+    }
+
+    let mut output = String::new();
+    for (i, name) in names.iter().enumerate() {
+        let mut to: Vec<&str> = tunnels[i].iter().map(|&j| names[j].as_str()).collect();
+        rng.shuffle(&mut to);
+        let lead = if to.len() == 1 {
+            "tunnel leads to valve"
+        } else {
+            "tunnels lead to valves"
+        };
+        writeln!(
+            output,
+            "Valve {} has flow rate={}; {} {}",
+            name,
+            rates[i],
+            lead,
+            to.join(", ")
+        )
+        .unwrap();
+    }
+    output
+}
+
+fn day17(rng: &mut Rng, jets: usize) -> String {
+    rng.word(&['<', '>'], jets) + "\n"
+}
+
+/// Distinct cubes packed into a box about big enough to hold them.
+fn day18(rng: &mut Rng, cubes: usize) -> String {
+    let side = ((cubes * 3) as f64).cbrt().ceil().max(3.0) as i64;
+    let cubes = cubes.min((side * side * side) as usize);
+    let mut seen = HashSet::new();
+    let mut output = String::new();
+    while seen.len() < cubes {
+        let cube = (rng.range(1, side), rng.range(1, side), rng.range(1, side));
+        if seen.insert(cube) {
+            writeln!(output, "{},{},{}", cube.0, cube.1, cube.2).unwrap();
+        }
+    }
+    output
+}
+
+fn day19(rng: &mut Rng, blueprints: usize) -> String {
+    let mut output = String::new();
+    for i in 1..=blueprints {
+        writeln!(
+            output,
+            "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. Each obsidian robot costs {} ore and {} clay. Each geode robot costs {} ore and {} obsidian.",
+            i,
+            rng.range(2, 4),
+            rng.range(2, 4),
+            rng.range(2, 4),
+            rng.range(5, 20),
+            rng.range(2, 4),
+            rng.range(5, 20)
+        )
+        .unwrap();
+    }
+    output
+}
+
+/// Numbers with repeats but exactly one zero.
+fn day20(rng: &mut Rng, count: usize) -> String {
+    // A lone number has nowhere to be mixed to
+    let count = count.max(2);
+    let zero = rng.below(count);
+    let mut output = String::new();
+    for i in 0..count {
+        let n = if i == zero {
+            0
+        } else {
+            let n = rng.range(1, 10000);
+            if rng.chance(0.5) {
+                -n
+            } else {
+                n
+            }
+        };
+        writeln!(output, "{}", n).unwrap();
+    }
+    output
+}
+
+/// Monkeys forming one expression tree under `root`, with `humn` one of
+/// the leaves. Every division comes out exact.
+fn day21(rng: &mut Rng, monkeys: usize) -> String {
+    let leaves = (monkeys / 2 + 1).max(2);
+    let mut seen: HashSet<String> = ["root", "humn"].iter().map(|s| s.to_string()).collect();
+    let mut name = |rng: &mut Rng| loop {
+        let name = rng.word(&LOWER, 4);
+        if seen.insert(name.clone()) {
+            break name;
+        }
+    };
+
+    let mut lines = vec![];
+    // (name, value) of each subtree not yet used by another monkey
+    let mut free: Vec<(String, i64)> = vec![];
+    for i in 0..leaves {
+        let value = rng.range(1, 20);
+        let leaf = if i == 0 {
+            String::from("humn")
+        } else {
+            name(rng)
+        };
+        lines.push(format!("{}: {}", leaf, value));
+        free.push((leaf, value));
+    }
+    while free.len() > 1 {
+        let (a, x) = free.swap_remove(rng.below(free.len()));
+        let (b, y) = free.swap_remove(rng.below(free.len()));
+        let (op, value) = if y != 0 && x % y == 0 && rng.chance(0.5) {
+            ('/', x / y)
+        } else if x.abs() < 100_000 && y.abs() < 100_000 && rng.chance(0.3) {
+            ('*', x * y)
+        } else if rng.chance(0.5) {
+            ('+', x + y)
+        } else {
+            ('-', x - y)
+        };
+        let monkey = if free.is_empty() {
+            String::from("root")
+        } else {
+            name(rng)
+        };
+        lines.push(format!("{}: {} {} {}", monkey, a, op, b));
+        free.push((monkey, value));
+    }
+    rng.shuffle(&mut lines);
+    lines.join("\n") + "\n"
+}
+
+/// A cube net in the same layout as the real inputs, with faces `side`
+/// squares across, and a path over it.
+fn day22(rng: &mut Rng, side: usize) -> String {
+    // Which faces are filled in, by face row and column
+    const NET: [&str; 4] = [" ##", " #", "##", "#"];
+    let mut output = String::new();
+    for (face_row, faces) in NET.iter().enumerate() {
+        for y in 0..side {
+            for (face_col, face) in faces.chars().enumerate() {
+                for x in 0..side {
+                    let c = if face == ' ' {
+                        ' '
+                    } else if face_row == 0 && face_col == 1 && x == 0 && y == 0 {
+                        // Where we start
+                        '.'
+                    } else if rng.chance(0.1) {
+                        '#'
+                    } else {
+                        '.'
+                    };
+                    output.push(c);
+                }
+            }
+            output.push('\n');
+        }
+    }
+    output.push('\n');
+    let side = side as i64;
+    write!(output, "{}", rng.range(1, side)).unwrap();
+    for _ in 0..4 * side {
+        write!(output, "{}{}", rng.pick(&['L', 'R']), rng.range(1, side)).unwrap();
+    }
+    output + "\n"
+}
+
+fn day23(rng: &mut Rng, side: usize) -> String {
+    let mut output = String::new();
+    for _ in 0..side {
+        let row: String = (0..side)
+            .map(|_| if rng.chance(0.4) { '#' } else { '.' })
+            .collect();
+        writeln!(output, "{}", row).unwrap();
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::get_day;
+
+    #[test]
+    fn test_rng() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        assert_eq!(a.next_u64(), b.next_u64());
+        assert!((0..1000).all(|_| (-3..=3).contains(&a.range(-3, 3))));
+        let mut items: Vec<u32> = (0..10).collect();
+        a.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_generators_parse() {
+        for generator in GENERATORS {
+            let Some(day) = get_day(generator.day) else {
+                continue;
+            };
+            for size in [1, generator.default_size] {
+                let input = generator.generate(size, 1);
+                assert_eq!(input, generator.generate(size, 1));
+                if let Err(e) = day.solver.parse(&input) {
+                    panic!("day {} size {}:\n{}", generator.day, size, e.diagnostic());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stress() {
+        // Days quick enough to solve in a debug build at the real size
+        for n in [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 20] {
+            let generator = get_generator(n).unwrap();
+            let input = generator.generate(generator.default_size, 2022);
+            let day = get_day(n).unwrap();
+            assert!(day.run(1, &input).unwrap().is_some());
+            day.run(2, &input).unwrap();
+        }
+
+        let input = day1(&mut Rng::new(0), 3);
+        assert_eq!(input.split("\n\n").count(), 3);
+    }
+}
@@ -0,0 +1,271 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
+
+/// Integer types that can be used as point coordinates.
+pub trait Coord: Copy + Ord + Add<Output = Self> + Sub<Output = Self> {
+    /// `self + delta`, or None if that doesn't fit in the type.
+    fn checked_offset(self, delta: i8) -> Option<Self>;
+}
+
+macro_rules! coord {
+    ($add:ident: $($t:ty),*) => {$(
+        impl Coord for $t {
+            fn checked_offset(self, delta: i8) -> Option<Self> {
+                self.$add(delta.into())
+            }
+        }
+    )*};
+}
+
+coord!(checked_add: i32, i64, isize);
+coord!(checked_add_signed: usize);
+
+/// A point on a 2D grid. `x` is the column and `y` the row, and like the
+/// puzzle inputs `y` grows downwards, so north is `-y`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T: Coord> Point2<T> {
+    /// The neighbouring point in direction `dir`, or None if it would
+    /// overflow, such as stepping north from row 0 of a `usize` grid.
+    pub fn checked_step(self, dir: impl Into<Direction8>) -> Option<Self> {
+        let (dx, dy) = dir.into().delta();
+        Some(Self::new(
+            self.x.checked_offset(dx)?,
+            self.y.checked_offset(dy)?,
+        ))
+    }
+
+    /// The neighbouring point in direction `dir`. Panics on overflow, the
+    /// same as the arithmetic it replaces.
+    pub fn step(self, dir: impl Into<Direction8>) -> Self {
+        self.checked_step(dir)
+            .expect("stepped outside the coordinate range")
+    }
+
+    /// Distance moving only along the axes.
+    pub fn manhattan(self, other: Self) -> T {
+        distance(self.x, other.x) + distance(self.y, other.y)
+    }
+
+    /// Distance when diagonal moves count as one step.
+    pub fn chebyshev(self, other: Self) -> T {
+        distance(self.x, other.x).max(distance(self.y, other.y))
+    }
+}
+
+fn distance<T: Coord>(a: T, b: T) -> T {
+    a.max(b) - a.min(b)
+}
+
+impl<T> From<(T, T)> for Point2<T> {
+    fn from((x, y): (T, T)) -> Self {
+        Self::new(x, y)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, scale: T) -> Self {
+        Self::new(self.x * scale, self.y * scale)
+    }
+}
+
+impl<T: AddAssign> AddAssign for Point2<T> {
+    fn add_assign(&mut self, other: Self) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl<T: SubAssign> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, other: Self) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// One of the four compass directions, in clockwise order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction4 {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction4 {
+    pub const ALL: [Direction4; 4] = [
+        Direction4::North,
+        Direction4::East,
+        Direction4::South,
+        Direction4::West,
+    ];
+
+    fn from_index(i: usize) -> Self {
+        Self::ALL[i % 4]
+    }
+
+    /// Quarter turn anticlockwise.
+    pub fn turn_left(self) -> Self {
+        Self::from_index(self as usize + 3)
+    }
+
+    /// Quarter turn clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::from_index(self as usize + 1)
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::from_index(self as usize + 2)
+    }
+
+    /// One step in this direction, as a point.
+    pub fn offset<T: From<i8>>(self) -> Point2<T> {
+        Direction8::from(self).offset()
+    }
+}
+
+/// The four compass directions and the diagonals between them, in
+/// clockwise order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    pub const ALL: [Direction8; 8] = [
+        Direction8::North,
+        Direction8::NorthEast,
+        Direction8::East,
+        Direction8::SouthEast,
+        Direction8::South,
+        Direction8::SouthWest,
+        Direction8::West,
+        Direction8::NorthWest,
+    ];
+
+    fn from_index(i: usize) -> Self {
+        Self::ALL[i % 8]
+    }
+
+    /// Eighth of a turn anticlockwise.
+    pub fn turn_left(self) -> Self {
+        Self::from_index(self as usize + 7)
+    }
+
+    /// Eighth of a turn clockwise.
+    pub fn turn_right(self) -> Self {
+        Self::from_index(self as usize + 1)
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::from_index(self as usize + 4)
+    }
+
+    fn delta(self) -> (i8, i8) {
+        match self {
+            Direction8::North => (0, -1),
+            Direction8::NorthEast => (1, -1),
+            Direction8::East => (1, 0),
+            Direction8::SouthEast => (1, 1),
+            Direction8::South => (0, 1),
+            Direction8::SouthWest => (-1, 1),
+            Direction8::West => (-1, 0),
+            Direction8::NorthWest => (-1, -1),
+        }
+    }
+
+    /// One step in this direction, as a point.
+    pub fn offset<T: From<i8>>(self) -> Point2<T> {
+        let (dx, dy) = self.delta();
+        Point2::new(dx.into(), dy.into())
+    }
+}
+
+impl From<Direction4> for Direction8 {
+    fn from(dir: Direction4) -> Self {
+        Self::from_index(dir as usize * 2)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        use Direction4::*;
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(West.turn_right(), North);
+        assert_eq!(East.reverse(), West);
+        for dir in Direction4::ALL {
+            assert_eq!(dir.turn_left().turn_left(), dir.reverse());
+            assert_eq!(dir.turn_right().turn_left(), dir);
+        }
+        assert_eq!(Direction8::North.turn_left(), Direction8::NorthWest);
+        assert_eq!(Direction8::from(South), Direction8::South);
+        assert_eq!(Direction8::SouthWest.reverse(), Direction8::NorthEast);
+    }
+
+    #[test]
+    fn test_steps() {
+        let p = Point2::new(3_usize, 0);
+        assert_eq!(p.step(Direction4::East), Point2::new(4, 0));
+        assert_eq!(p.step(Direction8::SouthWest), Point2::new(2, 1));
+        assert_eq!(p.checked_step(Direction4::North), None);
+        assert_eq!(Direction4::North.offset::<i64>(), Point2::new(0, -1));
+        let q = Point2::new(-1_i64, 2);
+        assert_eq!(q + Direction8::NorthEast.offset(), Point2::new(0, 1));
+        assert_eq!(q * 3 - q, Point2::new(-2, 4));
+    }
+
+    #[test]
+    fn test_distances() {
+        let p = Point2::new(-2_i32, 5);
+        let q = Point2::new(3, 1);
+        assert_eq!(p.manhattan(q), 9);
+        assert_eq!(p.chebyshev(q), 5);
+        assert_eq!(Point2::new(7_usize, 2).manhattan(Point2::new(4, 6)), 7);
+        assert_eq!(Point2::from((1, 2)).to_string(), "(1, 2)");
+    }
+}
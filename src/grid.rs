@@ -0,0 +1,240 @@
+use crate::geom::{Direction4, Direction8, Point2};
+use crate::parse::{Line, Lines, ParseError};
+use std::fmt;
+use std::iter::successors;
+use std::ops::{Index, IndexMut};
+
+/// A rectangle of cells stored row by row. Points are `x` across and `y`
+/// down from the top left, the same as `geom`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A `width` by `height` grid filled with `fill`.
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Parse a whole input with one cell per character. `cell` returns None
+    /// for characters that aren't allowed, and `expected` says what is.
+    pub fn from_str(
+        day: u32,
+        input: &str,
+        expected: &str,
+        cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        Self::from_lines(Lines::new(day, input), expected, cell)
+    }
+
+    /// Like `from_str`, for some of a day's lines. Short lines are padded
+    /// with spaces, since trailing ones tend to get trimmed.
+    pub fn from_lines<'a>(
+        lines: impl IntoIterator<Item = Line<'a>>,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let lines: Vec<Line> = lines.into_iter().collect();
+        let width = lines
+            .iter()
+            .map(|line| line.text.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut cells = Vec::with_capacity(width * lines.len());
+        for line in &lines {
+            let padding = std::iter::repeat(' ');
+            for (col, c) in line.text.chars().chain(padding).take(width).enumerate() {
+                cells.push(cell(c).ok_or_else(|| line.error_at(col + 1, expected))?);
+            }
+        }
+        Ok(Self {
+            width,
+            height: lines.len(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, p: Point2<usize>) -> bool {
+        p.x < self.width && p.y < self.height
+    }
+
+    pub fn get(&self, p: Point2<usize>) -> Option<&T> {
+        self.contains(p)
+            .then(|| &self.cells[p.y * self.width + p.x])
+    }
+
+    pub fn get_mut(&mut self, p: Point2<usize>) -> Option<&mut T> {
+        if self.contains(p) {
+            Some(&mut self.cells[p.y * self.width + p.x])
+        } else {
+            None
+        }
+    }
+
+    /// Every point in the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point2<usize>> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point2::new(x, y)))
+    }
+
+    /// Every point along with its cell, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<usize>, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {} is outside the grid", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// The points beyond `p` heading in `dir`, up to the edge of the grid.
+    pub fn ray(
+        &self,
+        p: Point2<usize>,
+        dir: impl Into<Direction8>,
+    ) -> impl Iterator<Item = Point2<usize>> + '_ {
+        let dir = dir.into();
+        successors(Some(p), move |q| q.checked_step(dir))
+            .skip(1)
+            .take_while(|&q| self.contains(q))
+    }
+
+    /// The up to four points next to `p` along the axes.
+    pub fn neighbours4(&self, p: Point2<usize>) -> impl Iterator<Item = Point2<usize>> + '_ {
+        Direction4::ALL
+            .into_iter()
+            .filter_map(move |dir| p.checked_step(dir))
+            .filter(|&q| self.contains(q))
+    }
+
+    /// The up to eight points around `p`, diagonals included.
+    pub fn neighbours8(&self, p: Point2<usize>) -> impl Iterator<Item = Point2<usize>> + '_ {
+        Direction8::ALL
+            .into_iter()
+            .filter_map(move |dir| p.checked_step(dir))
+            .filter(|&q| self.contains(q))
+    }
+
+    /// The first point, row by row, whose cell matches.
+    pub fn find(&self, mut matches: impl FnMut(&T) -> bool) -> Option<Point2<usize>> {
+        self.iter().find(|(_, cell)| matches(cell)).map(|(p, _)| p)
+    }
+
+    /// A grid the same shape, with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<Point2<usize>> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point2<usize>) -> &T {
+        self.get(p)
+            .unwrap_or_else(|| panic!("{} is outside the grid", p))
+    }
+}
+
+impl<T> IndexMut<Point2<usize>> for Grid<T> {
+    fn index_mut(&mut self, p: Point2<usize>) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{} is outside the grid", p))
+    }
+}
+
+/// One line per row, with each cell's own `Display` run together.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn digits() -> Grid<u32> {
+        Grid::from_str(0, "123\n456\n789\n", "a digit", |c| c.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn test_from_str() {
+        let grid = digits();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid[Point2::new(2, 0)], 3);
+        assert_eq!(grid.get(Point2::new(3, 0)), None);
+        assert_eq!(grid.to_string(), "123\n456\n789\n");
+
+        let err = Grid::from_str(8, "12\n3x\n", "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+
+        let ragged = Grid::from_str(22, "..#\n.\n", "a tile", Some).unwrap();
+        assert_eq!(ragged.to_string(), "..#\n.  \n");
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = digits();
+        assert_eq!(grid.row(1), &[4, 5, 6]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5, 8]);
+        assert_eq!(grid.rows().count(), 3);
+        let ray: Vec<_> = grid.ray(Point2::new(2, 2), Direction8::NorthWest).collect();
+        assert_eq!(ray, vec![Point2::new(1, 1), Point2::new(0, 0)]);
+        assert_eq!(grid.ray(Point2::new(0, 1), Direction4::West).count(), 0);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = digits();
+        assert_eq!(grid.neighbours4(Point2::new(1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8(Point2::new(1, 1)).count(), 8);
+        let corner: Vec<_> = grid.neighbours4(Point2::new(0, 0)).collect();
+        assert_eq!(corner, vec![Point2::new(1, 0), Point2::new(0, 1)]);
+        assert_eq!(grid.neighbours8(Point2::new(2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn test_find_and_map() {
+        let mut grid = digits();
+        assert_eq!(grid.find(|&d| d > 4), Some(Point2::new(1, 1)));
+        assert_eq!(grid.find(|&d| d > 9), None);
+        grid[Point2::new(0, 0)] = 0;
+        assert_eq!(grid.map(|&d| d % 2).row(0), &[0, 0, 1]);
+        assert_eq!(Grid::new(2, 1, '.').to_string(), "..\n");
+    }
+}
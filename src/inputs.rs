@@ -0,0 +1,182 @@
+use crate::YEAR;
+use std::fmt;
+use std::fs::{read_dir, read_to_string};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Directory, relative to the crate root, holding one subdirectory per year.
+pub const INPUT_ROOT: &str = "input";
+
+/// Which of a day's input files to use.
+///
+/// Examples are named `NN.txt`, `NNa.txt`, `NNb.txt`... and are numbered in
+/// that order. Real inputs are `dayN.txt`, and anybody else's input is
+/// `<name>_dayN.txt`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InputKind {
+    Example(usize),
+    Real,
+    Named(String),
+}
+
+impl InputKind {
+    /// Short name used to key per-input data such as known answers.
+    pub fn key(&self) -> String {
+        match self {
+            InputKind::Example(0) => String::from("example"),
+            InputKind::Example(n) => format!("example{}", n),
+            InputKind::Real => String::from("real"),
+            InputKind::Named(name) => name.clone(),
+        }
+    }
+}
+
+impl fmt::Display for InputKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputKind::Example(n) => write!(f, "example #{}", n),
+            InputKind::Real => write!(f, "real input"),
+            InputKind::Named(name) => write!(f, "{}'s input", name),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    Missing {
+        day: u32,
+        kind: InputKind,
+        tried: Vec<PathBuf>,
+    },
+    Unreadable {
+        path: PathBuf,
+        error: io::Error,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::Missing { day, kind, tried } => {
+                write!(f, "day {}: no {} found", day, kind)?;
+                if !tried.is_empty() {
+                    let tried: Vec<_> = tried.iter().map(|p| p.display().to_string()).collect();
+                    write!(f, " (looked for {})", tried.join(", "))?;
+                }
+                Ok(())
+            }
+            InputError::Unreadable { path, error } => {
+                write!(f, "could not read {}: {}", path.display(), error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// `input/<year>`, where every input and data file for the year lives.
+pub fn year_dir() -> PathBuf {
+    Path::new(INPUT_ROOT).join(YEAR.to_string())
+}
+
+fn is_example_name(name: &str, day: u32) -> bool {
+    let prefix = format!("{:02}", day);
+    match name
+        .strip_prefix(&prefix)
+        .and_then(|s| s.strip_suffix(".txt"))
+    {
+        Some("") => true,
+        Some(suffix) => suffix.len() == 1 && suffix.chars().all(|c| c.is_ascii_lowercase()),
+        None => false,
+    }
+}
+
+/// All example files for `day`, in example number order.
+pub fn example_paths(day: u32) -> Vec<PathBuf> {
+    let mut names: Vec<String> = match read_dir(year_dir()) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| e.file_name().into_string().ok())
+            .filter(|name| is_example_name(name, day))
+            .collect(),
+        Err(_) => vec![],
+    };
+    names.sort();
+    names.into_iter().map(|n| year_dir().join(n)).collect()
+}
+
+/// Where `name`'s input for `day` is kept.
+pub fn named_path(day: u32, name: &str) -> PathBuf {
+    year_dir().join(format!("{}_day{}.txt", name, day))
+}
+
+/// Path of the file `kind` refers to for `day`, if it exists.
+pub fn resolve(day: u32, kind: &InputKind) -> Result<PathBuf, InputError> {
+    let path = match kind {
+        InputKind::Example(n) => {
+            return example_paths(day)
+                .get(*n)
+                .cloned()
+                .ok_or_else(|| InputError::Missing {
+                    day,
+                    kind: kind.clone(),
+                    tried: vec![year_dir().join(format!("{:02}*.txt", day))],
+                });
+        }
+        InputKind::Real => year_dir().join(format!("day{}.txt", day)),
+        InputKind::Named(name) => named_path(day, name),
+    };
+
+    if path.is_file() {
+        Ok(path)
+    } else {
+        Err(InputError::Missing {
+            day,
+            kind: kind.clone(),
+            tried: vec![path],
+        })
+    }
+}
+
+/// Read the input `kind` refers to for `day`.
+pub fn load(day: u32, kind: &InputKind) -> Result<String, InputError> {
+    let path = resolve(day, kind)?;
+    read_to_string(&path).map_err(|error| InputError::Unreadable { path, error })
+}
+
+/// Read an arbitrary input file, with the same error type as `load`.
+pub fn load_path(path: &Path) -> Result<String, InputError> {
+    read_to_string(path).map_err(|error| InputError::Unreadable {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_example_order() {
+        let names: Vec<_> = example_paths(9)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_str().unwrap().to_string())
+            .collect();
+        assert_eq!(names, vec!["09.txt", "09a.txt"]);
+        assert!(example_paths(6).is_empty());
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(
+            resolve(1, &InputKind::Example(0)).unwrap(),
+            year_dir().join("01a.txt")
+        );
+        assert_eq!(
+            resolve(13, &InputKind::Named(String::from("chris"))).unwrap(),
+            year_dir().join("chris_day13.txt")
+        );
+        assert!(resolve(1, &InputKind::Example(1)).is_err());
+        assert!(resolve(99, &InputKind::Real).is_err());
+    }
+}
@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::ops::Index;
+
+/// Integer types an `Interner` can hand out. Ids count up from 0 with no
+/// gaps, so they can index a `Vec` or pick a bit in a set.
+pub trait Id: Copy + Eq + Hash + fmt::Debug {
+    /// The id for the `index`th name, or None if the type can't hold it.
+    fn from_index(index: usize) -> Option<Self>;
+    fn index(self) -> usize;
+
+    /// This id as a single bit, for sets of up to 64 ids kept in a `u64`.
+    fn bit(self) -> u64 {
+        1_u64
+            .checked_shl(self.index() as u32)
+            .expect("id too big for a 64 bit set")
+    }
+}
+
+macro_rules! id {
+    ($($t:ty),*) => {$(
+        impl Id for $t {
+            fn from_index(index: usize) -> Option<Self> {
+                index.try_into().ok()
+            }
+
+            fn index(self) -> usize {
+                self as usize
+            }
+        }
+    )*};
+}
+
+id!(u16, u32);
+
+/// Names given small dense ids, for puzzles where nodes are named by
+/// strings. Serializes as the list of names in id order.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Vec<String>", into = "Vec<String>")]
+pub struct Interner<I: Id = u32> {
+    ids: HashMap<String, I>,
+    names: Vec<String>,
+}
+
+impl<I: Id> Default for Interner<I> {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::new(),
+            names: vec![],
+        }
+    }
+}
+
+impl<I: Id> Interner<I> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// The id of `name`, giving it the next one if it doesn't have one yet.
+    /// Panics if the id type has run out.
+    pub fn get_or_intern(&mut self, name: &str) -> I {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        let id =
+            I::from_index(self.names.len()).unwrap_or_else(|| panic!("no ids left for {}", name));
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        id
+    }
+
+    /// The id of `name`, if it has one.
+    pub fn get(&self, name: &str) -> Option<I> {
+        self.ids.get(name).copied()
+    }
+
+    /// The name with id `id`, if it was handed out.
+    pub fn resolve(&self, id: I) -> Option<&str> {
+        self.names.get(id.index()).map(String::as_str)
+    }
+
+    /// Every id handed out so far, in order. They run from 0 to one less
+    /// than `len`, so `len` is also the size a bitset or lookup table needs.
+    pub fn ids(&self) -> impl Iterator<Item = I> {
+        (0..self.len()).map(|index| I::from_index(index).unwrap())
+    }
+
+    /// Every name with its id, in id order.
+    pub fn iter(&self) -> impl Iterator<Item = (I, &str)> {
+        self.ids().zip(self.names.iter().map(String::as_str))
+    }
+}
+
+/// The name with id `id`. Panics if there isn't one.
+impl<I: Id> Index<I> for Interner<I> {
+    type Output = str;
+
+    fn index(&self, id: I) -> &str {
+        self.resolve(id)
+            .unwrap_or_else(|| panic!("no name with id {:?}", id))
+    }
+}
+
+impl<'a, I: Id> FromIterator<&'a str> for Interner<I> {
+    fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
+        let mut interner = Self::new();
+        for name in iter {
+            interner.get_or_intern(name);
+        }
+        interner
+    }
+}
+
+impl<I: Id> TryFrom<Vec<String>> for Interner<I> {
+    type Error = String;
+
+    fn try_from(names: Vec<String>) -> Result<Self, Self::Error> {
+        let mut interner = Self::new();
+        for name in &names {
+            if interner.get(name).is_some() {
+                return Err(format!("{} is listed twice", name));
+            }
+            if I::from_index(interner.len()).is_none() {
+                return Err(format!("too many names for the id type at {}", name));
+            }
+            interner.get_or_intern(name);
+        }
+        Ok(interner)
+    }
+}
+
+impl<I: Id> From<Interner<I>> for Vec<String> {
+    fn from(interner: Interner<I>) -> Self {
+        interner.names
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut valves: Interner<u16> = Interner::new();
+        let aa = valves.get_or_intern("AA");
+        let bb = valves.get_or_intern("BB");
+        assert_eq!((aa, bb), (0, 1));
+        assert_eq!(valves.get_or_intern("AA"), aa);
+        assert_eq!(valves.get("BB"), Some(bb));
+        assert_eq!(valves.get("CC"), None);
+        assert_eq!(valves.resolve(bb), Some("BB"));
+        assert_eq!(valves.resolve(2), None);
+        assert_eq!(&valves[aa], "AA");
+        assert_eq!(valves.ids().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(aa.bit() | bb.bit(), 0b11);
+    }
+
+    #[test]
+    fn test_serde() {
+        let monkeys: Interner = ["root", "humn", "root", "dbpl"].into_iter().collect();
+        assert_eq!(monkeys.len(), 3);
+        let text = serde_json::to_string(&monkeys).unwrap();
+        assert_eq!(text, r#"["root","humn","dbpl"]"#);
+        let back: Interner = serde_json::from_str(&text).unwrap();
+        assert_eq!(back, monkeys);
+        assert!(serde_json::from_str::<Interner>(r#"["a","a"]"#).is_err());
+    }
+
+    #[test]
+    fn test_overflow() {
+        let names: Vec<String> = (0..=u16::MAX as u32 + 1).map(|n| n.to_string()).collect();
+        assert!(Interner::<u16>::try_from(names.clone()).is_err());
+        assert_eq!(Interner::<u32>::try_from(names).unwrap().len(), 65537);
+    }
+}
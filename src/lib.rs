@@ -1,7 +1,22 @@
-extern crate aoc_runner;
-
-#[macro_use]
-extern crate aoc_runner_derive;
+pub mod answers;
+pub mod bench;
+pub mod cycle;
+pub mod examples;
+pub mod gen;
+pub mod geom;
+pub mod grid;
+pub mod inputs;
+pub mod intern;
+pub mod logging;
+pub mod optimize;
+pub mod parse;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
+pub mod sparse;
+pub mod stepper;
+pub mod viz;
 
 pub mod day1;
 pub mod day10;
@@ -10,13 +25,16 @@ pub mod day12;
 pub mod day13;
 pub mod day14;
 pub mod day15;
-//pub mod day16;
+#[cfg(feature = "day16")]
+pub mod day16;
 pub mod day17;
 pub mod day18;
-//pub mod day19;
-pub mod day20;
-//pub mod day21;
+#[cfg(feature = "day19")]
+pub mod day19;
 pub mod day2;
+pub mod day20;
+#[cfg(feature = "day21")]
+pub mod day21;
 pub mod day22;
 pub mod day23;
 pub mod day3;
@@ -27,4 +45,105 @@ pub mod day7;
 pub mod day8;
 pub mod day9;
 
-aoc_lib! { year = 2022 }
+use solution::Day;
+
+pub const YEAR: u32 = 2022;
+
+/// An example test for one registered part of a day, named after the part.
+#[cfg(test)]
+macro_rules! example_test {
+    ($day:literal, 1) => {
+        #[test]
+        fn part1() {
+            $crate::examples::assert_examples($day, 1);
+        }
+    };
+    ($day:literal, 2) => {
+        #[test]
+        fn part2() {
+            $crate::examples::assert_examples($day, 2);
+        }
+    };
+}
+
+/// Define `DAYS` from its entries, along with `example_tests::dayN::partN`
+/// for every part each day registers, so new days get tests as they are
+/// added.
+macro_rules! registry {
+    ($(
+        $(#[$attr:meta])*
+        Day::new($day:literal, &[$($part:tt),*], &$module:ident::$solver:ident)
+            $(.$flag:ident())*,
+    )*) => {
+        /// Every day that can be run, and which of its parts are implemented.
+        /// Unfinished days are behind their own cargo feature, see `wip`.
+        pub static DAYS: &[Day] = &[$(
+            $(#[$attr])*
+            Day::new($day, &[$($part),*], &$module::$solver)$(.$flag())*,
+        )*];
+
+        #[cfg(test)]
+        mod example_tests {
+            $(
+                $(#[$attr])*
+                mod $module {
+                    $(example_test!($day, $part);)*
+                }
+            )*
+        }
+    };
+}
+
+registry! {
+    Day::new(1, &[1, 2], &day1::Day1),
+    Day::new(2, &[1, 2], &day2::Day2),
+    Day::new(3, &[1, 2], &day3::Day3),
+    Day::new(4, &[1, 2], &day4::Day4),
+    Day::new(5, &[1, 2], &day5::Day5),
+    Day::new(6, &[1, 2], &day6::Day6),
+    Day::new(7, &[1, 2], &day7::Day7),
+    Day::new(8, &[1, 2], &day8::Day8),
+    Day::new(9, &[1, 2], &day9::Day9),
+    Day::new(10, &[1, 2], &day10::Day10),
+    Day::new(11, &[1, 2], &day11::Day11),
+    Day::new(12, &[1, 2], &day12::Day12),
+    Day::new(13, &[1, 2], &day13::Day13),
+    Day::new(14, &[1, 2], &day14::Day14),
+    Day::new(15, &[1, 2], &day15::Day15),
+    #[cfg(feature = "day16")]
+    Day::new(16, &[1, 2], &day16::Day16).experimental(),
+    Day::new(17, &[1, 2], &day17::Day17),
+    Day::new(18, &[1, 2], &day18::Day18),
+    #[cfg(feature = "day19")]
+    Day::new(19, &[1, 2], &day19::Day19).experimental(),
+    Day::new(20, &[1], &day20::Day20),
+    #[cfg(feature = "day21")]
+    Day::new(21, &[1], &day21::Day21).experimental(),
+    Day::new(22, &[1], &day22::Day22),
+    Day::new(23, &[1, 2], &day23::Day23),
+}
+
+pub fn get_day(day: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::inputs::{load, InputKind};
+
+    #[test]
+    fn test_registry() {
+        let input = load(1, &InputKind::Example(0)).unwrap();
+        let day = get_day(1).unwrap();
+        assert_eq!(day.run(1, &input), Ok(Some(String::from("24000"))));
+        assert_eq!(day.run(2, &input), Ok(Some(String::from("45000"))));
+        assert_eq!(get_day(20).unwrap().run(2, &input), Ok(None));
+        assert!(day.run(1, "1000\nabc\n").is_err());
+        assert!(get_day(26).is_none());
+        assert_eq!(get_day(16).is_some(), cfg!(feature = "day16"));
+        assert!(DAYS
+            .iter()
+            .all(|d| d.is_stable() || [16, 19, 21].contains(&d.day)));
+    }
+}
@@ -0,0 +1,60 @@
+use env_logger::Builder;
+
+/// Environment variable holding the log filter when none is given on the
+/// command line.
+pub const LOG_ENV: &str = "AOC_LOG";
+
+/// Level used when nothing else is asked for, so solvers are quiet.
+pub const DEFAULT_FILTER: &str = "warn";
+
+fn is_day(name: &str) -> bool {
+    name.strip_prefix("day")
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Expand a filter such as `day16=debug,info` so bare day names pick out
+/// this crate's modules: `aoc2022::day16=debug,info`. Anything else is
+/// passed through in env_logger's usual syntax.
+pub fn expand_filter(filter: &str) -> String {
+    let directives: Vec<String> = filter
+        .split(',')
+        .map(|directive| {
+            let name = directive.split('=').next().unwrap_or_default();
+            if is_day(name) {
+                format!("{}::{}", env!("CARGO_CRATE_NAME"), directive)
+            } else {
+                directive.to_string()
+            }
+        })
+        .collect();
+    directives.join(",")
+}
+
+/// Start logging to stderr with `filter`, or `$AOC_LOG`, or just warnings.
+pub fn init(filter: Option<&str>) {
+    let from_env = std::env::var(LOG_ENV).ok();
+    let filter = filter.or(from_env.as_deref()).unwrap_or(DEFAULT_FILTER);
+    // Only fails if a logger is already set, which is fine
+    let _ = Builder::new()
+        .parse_filters(&expand_filter(filter))
+        .format_timestamp(None)
+        .try_init();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_expand_filter() {
+        assert_eq!(
+            expand_filter("day16=debug,info"),
+            "aoc2022::day16=debug,info"
+        );
+        assert_eq!(expand_filter("day20"), "aoc2022::day20");
+        assert_eq!(
+            expand_filter("dayz=trace,rayon=off"),
+            "dayz=trace,rayon=off"
+        );
+    }
+}
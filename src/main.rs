@@ -1,3 +1,426 @@
-use aoc_runner_derive::aoc_main;
+use aoc2022::answers::Answers;
+use aoc2022::bench::{
+    append_history, bench_day, bench_path, load_baseline, regressions, save_baseline, BenchRecord,
+    Stats, BASELINE_FILE, HISTORY_FILE,
+};
+use aoc2022::gen::get_generator;
+use aoc2022::inputs::{load, load_path, named_path, InputKind};
+use aoc2022::logging;
+use aoc2022::runner::{format_duration, run_day, DayRun};
+use aoc2022::scaffold;
+use aoc2022::solution::Day;
+use aoc2022::stepper::{Mode, Predicate, Stepper};
+use aoc2022::viz::{Backend, Format, Headless, Terminal, Viz};
+use aoc2022::{get_day, DAYS};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::fs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
 
-aoc_main! { lib = aoc2022 }
+#[derive(Parser)]
+#[command(name = "aoc2022", about = "Advent of Code 2022 solutions")]
+struct Cli {
+    /// Log filter such as `day16=debug` or `trace`, overriding $AOC_LOG
+    #[arg(short, long, global = true, value_name = "FILTER")]
+    log: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one day, or every day with --all
+    Run(RunArgs),
+    /// Run every day and report any that fail or give a known wrong answer
+    Check(CheckArgs),
+    /// Time each phase over several iterations and compare to the baseline
+    Bench(BenchArgs),
+    /// Start a new day from src/template.rs
+    NewDay(NewDayArgs),
+    /// Watch a day's simulation, or write its frames to files
+    Visualize(VisualizeArgs),
+    /// Make a random input for a day, the same every time for one seed
+    Gen(GenArgs),
+}
+
+/// Which input to feed a day. The real input unless told otherwise.
+#[derive(Args)]
+struct InputArgs {
+    /// Input file to use instead of the day's real input
+    #[arg(short, long, requires = "day", conflicts_with_all = ["example", "user"])]
+    input: Option<PathBuf>,
+    /// Use the day's example input, optionally picking which one
+    #[arg(short, long, num_args = 0..=1, default_missing_value = "0")]
+    example: Option<usize>,
+    /// Use someone else's input, stored as <USER>_dayN.txt
+    #[arg(short, long, conflicts_with = "example")]
+    user: Option<String>,
+}
+
+impl InputArgs {
+    fn kind(&self) -> InputKind {
+        match (self.example, &self.user) {
+            (Some(n), _) => InputKind::Example(n),
+            (_, Some(user)) => InputKind::Named(user.clone()),
+            _ => InputKind::Real,
+        }
+    }
+
+    fn read(&self, day: u32) -> Result<String, String> {
+        match &self.input {
+            Some(path) => load_path(path).map_err(|e| e.to_string()),
+            None => read_input(day, &self.kind()),
+        }
+    }
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Day to run
+    #[arg(short, long, required_unless_present = "all")]
+    day: Option<u32>,
+    /// Only run this part
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+    #[command(flatten)]
+    input: InputArgs,
+    /// Run every registered day
+    #[arg(long, conflicts_with = "day")]
+    all: bool,
+    /// Include experimental days when running --all
+    #[arg(long, requires = "all")]
+    experimental: bool,
+}
+
+#[derive(Args)]
+struct CheckArgs {
+    /// Also check experimental days
+    #[arg(long)]
+    experimental: bool,
+}
+
+#[derive(Args)]
+struct BenchArgs {
+    /// Day to benchmark, defaults to all of them
+    #[arg(short, long)]
+    day: Option<u32>,
+    /// Number of times to run each day
+    #[arg(short = 'n', long, default_value_t = 10)]
+    iterations: u32,
+    /// Fail if any median is this many percent slower than the baseline
+    #[arg(short, long, default_value_t = 10.0)]
+    threshold: f64,
+    /// Store these results as the new baseline instead of comparing
+    #[arg(long)]
+    save_baseline: bool,
+    /// Include experimental days when no day is given
+    #[arg(long, conflicts_with = "day")]
+    experimental: bool,
+}
+
+#[derive(Args)]
+struct NewDayArgs {
+    /// Day to create
+    #[arg(value_parser = clap::value_parser!(u32).range(1..=25))]
+    day: u32,
+    /// Type of one parsed item of the input
+    #[arg(long, default_value = "usize")]
+    input_type: String,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum FrameFormat {
+    Text,
+    Ppm,
+}
+
+#[derive(Args)]
+struct VisualizeArgs {
+    /// Day to visualize
+    #[arg(short, long)]
+    day: u32,
+    #[command(flatten)]
+    input: InputArgs,
+    /// Write numbered frame files here instead of drawing in the terminal
+    #[arg(short, long)]
+    out: Option<PathBuf>,
+    /// File format for --out
+    #[arg(short, long, value_enum, default_value_t = FrameFormat::Text, requires = "out")]
+    format: FrameFormat,
+    /// Pixels per cell for PPM frames
+    #[arg(long, default_value_t = 4)]
+    scale: usize,
+    /// Milliseconds to pause between frames in the terminal
+    #[arg(long, default_value_t = 50)]
+    delay: u64,
+    /// Pause before the first tick and step through from there
+    #[arg(long, conflicts_with_all = ["break_at", "until"])]
+    step: bool,
+    /// Run to this tick and pause
+    #[arg(long, value_name = "TICK", conflicts_with = "until")]
+    break_at: Option<u64>,
+    /// Run until a watched value matches, e.g. round==10, and pause
+    #[arg(long, value_name = "PRED")]
+    until: Option<Predicate>,
+}
+
+#[derive(Args)]
+struct GenArgs {
+    /// Day to make input for
+    #[arg(short, long)]
+    day: u32,
+    /// How big to make it, defaults to about the size of a real input
+    #[arg(short, long)]
+    size: Option<usize>,
+    /// Seed for the random numbers
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Save as <NAME>_dayN.txt, to run with --user NAME, instead of printing
+    #[arg(long, value_name = "NAME")]
+    save: Option<String>,
+}
+
+fn lookup_day(day: u32) -> Result<&'static Day, String> {
+    get_day(day).ok_or_else(|| format!("day {} is not registered", day))
+}
+
+/// The requested day, or every day when `day` is `None`. Experimental days
+/// are only included in the latter if asked for.
+fn select_days(day: Option<u32>, experimental: bool) -> Result<Vec<&'static Day>, String> {
+    match day {
+        Some(day) => Ok(vec![lookup_day(day)?]),
+        None => Ok(DAYS
+            .iter()
+            .filter(|d| experimental || d.is_stable())
+            .collect()),
+    }
+}
+
+fn read_input(day: u32, kind: &InputKind) -> Result<String, String> {
+    load(day, kind).map_err(|e| e.to_string())
+}
+
+fn load_answers() -> Result<Answers, String> {
+    Answers::load_default().map_err(|e| e.to_string())
+}
+
+/// Print each part's answer, along with what `answers` says about it when
+/// we know which input produced it.
+fn print_run(run: &DayRun, answers: &Answers, kind: Option<&InputKind>) {
+    println!(
+        "Day {} (parse {})",
+        run.day,
+        format_duration(run.parse_time)
+    );
+    for part in &run.parts {
+        let verdict = match kind {
+            Some(kind) => format!(
+                " [{}]",
+                answers.check(run.day, kind, part.part, &part.answer)
+            ),
+            None => String::new(),
+        };
+        println!(
+            "  Part {}: {} ({}){}",
+            part.part,
+            part.answer,
+            format_duration(part.elapsed),
+            verdict
+        );
+    }
+}
+
+fn run(args: RunArgs) -> Result<(), String> {
+    let parts: Vec<u8> = match args.part {
+        Some(p) => vec![p],
+        None => vec![1, 2],
+    };
+    let days = select_days(args.day, args.experimental)?;
+    let kind = args.input.kind();
+    let answers = load_answers()?;
+    for day in days {
+        let input = args.input.read(day.day)?;
+        let run = run_day(day, &input, &parts).map_err(|e| e.diagnostic())?;
+        // An arbitrary file has no known answers
+        let known = args.input.input.is_none().then_some(&kind);
+        print_run(&run, &answers, known);
+    }
+    Ok(())
+}
+
+fn check(args: CheckArgs) -> Result<(), String> {
+    let answers = load_answers()?;
+    let mut failed = vec![];
+    for day in select_days(None, args.experimental)? {
+        let input = match read_input(day.day, &InputKind::Real) {
+            Ok(input) => input,
+            Err(e) => {
+                println!("Day {:>2}: SKIP ({})", day.day, e);
+                continue;
+            }
+        };
+        match panic::catch_unwind(AssertUnwindSafe(|| run_day(day, &input, &[1, 2]))) {
+            Ok(Ok(run)) => {
+                let mut wrong = false;
+                let results: Vec<_> = run
+                    .parts
+                    .iter()
+                    .map(|p| {
+                        let verdict = answers.check(day.day, &InputKind::Real, p.part, &p.answer);
+                        wrong |= verdict.is_wrong();
+                        format!("{} [{}]", p.answer, verdict)
+                    })
+                    .collect();
+                let status = if wrong { "WRONG" } else { "ok" };
+                println!("Day {:>2}: {} {}", day.day, status, results.join(", "));
+                if wrong {
+                    failed.push(day.day);
+                }
+            }
+            Ok(Err(e)) => {
+                println!("Day {:>2}: PARSE ERROR\n{}", day.day, e.diagnostic());
+                failed.push(day.day);
+            }
+            Err(_) => {
+                println!("Day {:>2}: PANIC", day.day);
+                failed.push(day.day);
+            }
+        }
+    }
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("days failed: {:?}", failed))
+    }
+}
+
+fn print_stats(phase: &str, stats: &Stats) {
+    print!(
+        "  {} {} (p95 {})",
+        phase,
+        format_duration(Duration::from_nanos(stats.median)),
+        format_duration(Duration::from_nanos(stats.p95))
+    );
+}
+
+fn bench(args: BenchArgs) -> Result<(), String> {
+    if args.iterations == 0 {
+        return Err(String::from("need at least one iteration"));
+    }
+    let days = select_days(args.day, args.experimental)?;
+    let mut results = vec![];
+    for day in days {
+        let input = read_input(day.day, &InputKind::Real)?;
+        let result = bench_day(day, &input, args.iterations).map_err(|e| e.diagnostic())?;
+        print!("Day {:>2}:", day.day);
+        for (phase, stats) in &result.phases {
+            print_stats(phase, stats);
+        }
+        println!();
+        results.push(result);
+    }
+
+    let record = BenchRecord::new(args.iterations, results);
+    append_history(&bench_path(HISTORY_FILE), &record).map_err(|e| e.to_string())?;
+    let baseline_path = bench_path(BASELINE_FILE);
+    if args.save_baseline {
+        save_baseline(&baseline_path, &record).map_err(|e| e.to_string())?;
+        println!("Saved baseline to {}", baseline_path.display());
+        return Ok(());
+    }
+    let Some(baseline) = load_baseline(&baseline_path).map_err(|e| e.to_string())? else {
+        println!(
+            "No baseline at {}, run with --save-baseline to create one",
+            baseline_path.display()
+        );
+        return Ok(());
+    };
+    let found = regressions(&baseline, &record, args.threshold);
+    if found.is_empty() {
+        return Ok(());
+    }
+    for regression in &found {
+        println!("REGRESSION {}", regression);
+    }
+    Err(format!(
+        "{} phase(s) regressed by more than {}%",
+        found.len(),
+        args.threshold
+    ))
+}
+
+fn new_day(args: NewDayArgs) -> Result<(), String> {
+    let touched = scaffold::new_day(args.day, &args.input_type).map_err(|e| e.to_string())?;
+    for path in touched {
+        println!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+fn visualize(args: VisualizeArgs) -> Result<(), String> {
+    let day = lookup_day(args.day)?;
+    let input = args.input.read(day.day)?;
+    let parsed = day.solver.parse(&input).map_err(|e| e.diagnostic())?;
+    let mut backend: Box<dyn Backend> = match &args.out {
+        Some(dir) => {
+            let format = match args.format {
+                FrameFormat::Text => Format::Text,
+                FrameFormat::Ppm => Format::Ppm(args.scale),
+            };
+            Box::new(Headless::new(dir, format).map_err(|e| e.to_string())?)
+        }
+        None => Box::new(Terminal::stdout(Duration::from_millis(args.delay))),
+    };
+    let mode = match (args.step, args.break_at, args.until) {
+        (true, _, _) => Some(Mode::Pause),
+        (_, Some(tick), _) => Some(Mode::RunTo(tick)),
+        (_, _, Some(predicate)) => Some(Mode::Until(predicate)),
+        _ => None,
+    };
+    let mut viz = Viz::new(backend.as_mut());
+    if let Some(mode) = mode {
+        viz = viz.with_stepper(Stepper::stdio(mode));
+    }
+    if !day.solver.visualize(parsed.as_ref(), &mut viz) && !viz.has_quit() {
+        return Err(format!("day {} has nothing to visualize", day.day));
+    }
+    viz.finish().map_err(|e| e.to_string())
+}
+
+fn gen(args: GenArgs) -> Result<(), String> {
+    let generator =
+        get_generator(args.day).ok_or_else(|| format!("no generator for day {}", args.day))?;
+    let size = args.size.unwrap_or(generator.default_size);
+    let input = generator.generate(size, args.seed);
+    match args.save {
+        Some(name) => {
+            let path = named_path(args.day, &name);
+            fs::write(&path, input).map_err(|e| format!("{}: {}", path.display(), e))?;
+            println!("wrote {} ({} {})", path.display(), size, generator.size);
+        }
+        None => print!("{}", input),
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    logging::init(cli.log.as_deref());
+    let result = match cli.command {
+        Command::Run(args) => run(args),
+        Command::Check(args) => check(args),
+        Command::Bench(args) => bench(args),
+        Command::NewDay(args) => new_day(args),
+        Command::Visualize(args) => visualize(args),
+        Command::Gen(args) => gen(args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
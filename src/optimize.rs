@@ -0,0 +1,189 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A maximisation problem, searched one decision at a time from a start
+/// state. Every state is also a possible answer: stopping there is worth
+/// its `value`.
+pub trait Problem {
+    type State: Clone;
+    type Key: Eq + Hash;
+
+    /// The states one more decision away. Empty once nothing is left to
+    /// decide.
+    fn branch(&self, state: &Self::State) -> Vec<Self::State>;
+
+    /// What `state` is worth if no more decisions are made.
+    fn value(&self, state: &Self::State) -> i64;
+
+    /// The most any state reachable from `state` could be worth. It must
+    /// never be less than the real best, or the answer may not be optimal.
+    fn bound(&self, state: &Self::State) -> i64;
+
+    /// States with the same key have to gain the same from there on, so of
+    /// those only the one with the highest value is worth exploring.
+    fn key(&self, state: &Self::State) -> Self::Key;
+}
+
+/// How much work a search did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// States whose branches were generated.
+    pub expanded: u64,
+    /// States dropped because their bound couldn't beat the best so far.
+    pub bounded: u64,
+    /// States dropped because one with the same key was worth as much.
+    pub dominated: u64,
+}
+
+/// The best state found, which is proven optimal once the search finishes.
+#[derive(Clone, Debug)]
+pub struct Best<S> {
+    pub state: S,
+    pub value: i64,
+    pub stats: Stats,
+}
+
+/// Depth first branch and bound from `start`, returning a state no other
+/// reachable state is worth more than.
+pub fn maximize<P: Problem>(problem: &P, start: P::State) -> Best<P::State> {
+    maximize_until(problem, start, |_| true).expect("search was not stopped")
+}
+
+/// Like `maximize`, calling `inspect` before each state is expanded. The
+/// search gives up with None as soon as `inspect` returns false.
+pub fn maximize_until<P: Problem>(
+    problem: &P,
+    start: P::State,
+    mut inspect: impl FnMut(&Stats) -> bool,
+) -> Option<Best<P::State>> {
+    let mut stats = Stats::default();
+    let mut best_value = problem.value(&start);
+    let mut best = start.clone();
+    let mut seen: HashMap<P::Key, i64> = HashMap::new();
+    let mut stack = vec![start];
+
+    while let Some(state) = stack.pop() {
+        // The best may have improved since this was pushed
+        if problem.bound(&state) <= best_value {
+            stats.bounded += 1;
+            continue;
+        }
+        if !inspect(&stats) {
+            return None;
+        }
+        stats.expanded += 1;
+
+        let mut children = vec![];
+        for next in problem.branch(&state) {
+            let value = problem.value(&next);
+            match seen.entry(problem.key(&next)) {
+                Entry::Occupied(mut entry) => {
+                    if *entry.get() >= value {
+                        stats.dominated += 1;
+                        continue;
+                    }
+                    entry.insert(value);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(value);
+                }
+            }
+            if value > best_value {
+                best_value = value;
+                best = next.clone();
+            }
+            let bound = problem.bound(&next);
+            if bound <= best_value {
+                stats.bounded += 1;
+                continue;
+            }
+            children.push((bound, next));
+        }
+
+        // Most promising on top, so good answers turn up early and prune more
+        children.sort_by_key(|(bound, _)| *bound);
+        stack.extend(children.into_iter().map(|(_, next)| next));
+    }
+
+    Some(Best {
+        state: best,
+        value: best_value,
+        stats,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// 0/1 knapsack: take or leave each item in turn.
+    struct Knapsack {
+        capacity: i64,
+        // (weight, value), best value per weight first
+        items: Vec<(i64, i64)>,
+    }
+
+    // (next item, weight used, value so far)
+    type Packed = (usize, i64, i64);
+
+    impl Problem for Knapsack {
+        type State = Packed;
+        type Key = (usize, i64);
+
+        fn branch(&self, &(i, weight, value): &Packed) -> Vec<Packed> {
+            let Some(&(w, v)) = self.items.get(i) else {
+                return vec![];
+            };
+            let mut next = vec![(i + 1, weight, value)];
+            if weight + w <= self.capacity {
+                next.push((i + 1, weight + w, value + v));
+            }
+            next
+        }
+
+        fn value(&self, state: &Packed) -> i64 {
+            state.2
+        }
+
+        fn bound(&self, &(i, weight, value): &Packed) -> i64 {
+            // Fill what's left with fractions of the remaining items
+            let mut room = self.capacity - weight;
+            let mut bound = value;
+            for &(w, v) in &self.items[i..] {
+                if w <= room {
+                    room -= w;
+                    bound += v;
+                } else {
+                    bound += v * room / w;
+                    break;
+                }
+            }
+            bound
+        }
+
+        fn key(&self, &(i, weight, _): &Packed) -> (usize, i64) {
+            (i, weight)
+        }
+    }
+
+    #[test]
+    fn test_maximize() {
+        let problem = Knapsack {
+            capacity: 10,
+            items: vec![(4, 40), (6, 42), (5, 25), (3, 12)],
+        };
+        let best = maximize(&problem, (0, 0, 0));
+        assert_eq!(best.value, 82);
+        assert_eq!((best.state.1, best.state.2), (10, 82));
+        assert!(best.stats.expanded < 16);
+        assert!(best.stats.bounded > 0);
+
+        let mut calls = 0;
+        let stopped = maximize_until(&problem, (0, 0, 0), |_| {
+            calls += 1;
+            calls < 2
+        });
+        assert!(stopped.is_none());
+    }
+}
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Where and why an input failed to parse. Lines and columns count from 1.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub text: String,
+}
+
+impl ParseError {
+    /// The error message followed by the offending line with a caret under
+    /// the column that went wrong.
+    pub fn diagnostic(&self) -> String {
+        let gutter = " ".repeat(self.line.to_string().len());
+        format!(
+            "{}\n{} |\n{} | {}\n{} | {}^",
+            self,
+            gutter,
+            self.line,
+            self.text,
+            gutter,
+            " ".repeat(self.column.saturating_sub(1))
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// One line of a day's input, carrying enough context to build a
+/// `ParseError` pointing into it.
+#[derive(Clone, Copy, Debug)]
+pub struct Line<'a> {
+    pub day: u32,
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn error_at(&self, column: usize, expected: &str) -> ParseError {
+        ParseError {
+            day: self.day,
+            line: self.number,
+            column,
+            expected: expected.to_string(),
+            text: self.text.to_string(),
+        }
+    }
+
+    /// Error pointing at `token`, which must be a slice of this line.
+    pub fn error_in(&self, token: &str, expected: &str) -> ParseError {
+        self.error_at(self.column_of(token), expected)
+    }
+
+    /// Parse `token`, a slice of this line, reporting its position on failure.
+    pub fn parse<T: FromStr>(&self, token: &str, expected: &str) -> Result<T, ParseError> {
+        token
+            .parse::<T>()
+            .map_err(|_| self.error_in(token, expected))
+    }
+
+    /// Error for a line that did not match the `scan_fmt!` pattern `format`.
+    /// The column is the first place the line stops following the literal
+    /// parts of the pattern.
+    pub fn scan_error(&self, format: &str) -> ParseError {
+        self.scan_error_in(self.text, format)
+    }
+
+    /// Like `scan_error`, for a pattern applied to `token`, a slice of this line.
+    pub fn scan_error_in(&self, token: &str, format: &str) -> ParseError {
+        let offset = self.column_of(token) - 1;
+        let column = offset + mismatch(token, format) + 1;
+        self.error_at(column, &format!("`{}`", format))
+    }
+
+    fn column_of(&self, token: &str) -> usize {
+        let start = self.text.as_ptr() as usize;
+        let offset = (token.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|&o| o <= self.text.len())
+            .unwrap_or(0);
+        self.text[..offset].chars().count() + 1
+    }
+}
+
+fn looks_like_integer(token: &[char]) -> bool {
+    let digits = token.strip_prefix(&['-']).unwrap_or(token);
+    !digits.is_empty() && digits.iter().all(|c| c.is_ascii_digit())
+}
+
+/// Character index where `text` stops matching `format`, treating each `{}`
+/// as a word or signed number.
+fn mismatch(text: &str, format: &str) -> usize {
+    let text: Vec<char> = text.chars().collect();
+    let format: Vec<char> = format.chars().collect();
+    let mut i = 0;
+    let mut j = 0;
+    let mut fields = vec![];
+    while j < format.len() {
+        if format[j] == '{' && format.get(j + 1) == Some(&'}') {
+            j += 2;
+            let stop = format.get(j);
+            let start = i;
+            while i < text.len()
+                && Some(&text[i]) != stop
+                && (text[i].is_alphanumeric() || (i == start && "+-".contains(text[i])))
+            {
+                i += 1;
+            }
+            if i == start {
+                return i;
+            }
+            fields.push(start..i);
+        } else if i < text.len() && text[i] == format[j] {
+            i += 1;
+            j += 1;
+        } else {
+            return i;
+        }
+    }
+    if i < text.len() {
+        // Trailing garbage after an otherwise good line
+        return i;
+    }
+
+    // The shape matched, so one of the values must be bad. Numbers are the
+    // usual suspects; single characters are usually `char` fields.
+    let bad: Vec<_> = fields
+        .iter()
+        .filter(|f| !looks_like_integer(&text[(*f).clone()]))
+        .collect();
+    let field = bad
+        .iter()
+        .find(|f| f.len() > 1)
+        .or(bad.last())
+        .copied()
+        .or(fields.first());
+    field.map_or(0, |f| f.start)
+}
+
+/// Numbered lines of a day's input.
+pub struct Lines<'a> {
+    day: u32,
+    iter: std::str::Lines<'a>,
+    number: usize,
+}
+
+impl<'a> Lines<'a> {
+    pub fn new(day: u32, input: &'a str) -> Self {
+        Self {
+            day,
+            iter: input.lines(),
+            number: 0,
+        }
+    }
+
+    /// The next line, or an error at the end of the input saying what
+    /// should have been there.
+    pub fn next_or(&mut self, expected: &str) -> Result<Line<'a>, ParseError> {
+        match self.next() {
+            Some(line) => Ok(line),
+            None => Err(ParseError {
+                day: self.day,
+                line: self.number + 1,
+                column: 1,
+                expected: expected.to_string(),
+                text: String::new(),
+            }),
+        }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Line<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let text = self.iter.next()?;
+        self.number += 1;
+        Some(Line {
+            day: self.day,
+            number: self.number,
+            text,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_column() {
+        let line = Line {
+            day: 1,
+            number: 3,
+            text: "12 x4",
+        };
+        let err = line.parse::<u64>(&line.text[3..], "a number").unwrap_err();
+        assert_eq!((err.line, err.column), (3, 4));
+        assert_eq!(
+            err.diagnostic(),
+            "day 1, line 3, column 4: expected a number\n  |\n3 | 12 x4\n  |    ^"
+        );
+    }
+
+    #[test]
+    fn test_scan_error() {
+        let line = Line {
+            day: 4,
+            number: 1,
+            text: "2-4,6_8",
+        };
+        assert_eq!(line.scan_error("{}-{},{}-{}").column, 6);
+
+        let line = Line {
+            day: 4,
+            number: 1,
+            text: "2-4,6-8 ",
+        };
+        assert_eq!(line.scan_error("{}-{},{}-{}").column, 8);
+
+        let line = Line {
+            day: 14,
+            number: 1,
+            text: "498,4 -> 498,x",
+        };
+        let err = line.scan_error_in(&line.text[9..], "{},{}");
+        assert_eq!(err.column, 14);
+    }
+
+    #[test]
+    fn test_next_or() {
+        let mut lines = Lines::new(11, "Monkey 0:\n");
+        assert_eq!(lines.next_or("a monkey").unwrap().number, 1);
+        let err = lines.next_or("starting items").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 1));
+    }
+}
@@ -0,0 +1,82 @@
+use crate::parse::ParseError;
+use crate::solution::Day;
+use std::time::{Duration, Instant};
+
+/// Answer to one part along with how long it took to compute.
+#[derive(Clone, Debug)]
+pub struct PartRun {
+    pub part: u8,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+/// Result of parsing and solving one day's input.
+#[derive(Clone, Debug)]
+pub struct DayRun {
+    pub day: u32,
+    pub parse_time: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+/// Parse `input` once and run each requested part that `day` implements.
+pub fn run_day(day: &Day, input: &str, parts: &[u8]) -> Result<DayRun, ParseError> {
+    let start = Instant::now();
+    let parsed = day.solver.parse(input)?;
+    let parse_time = start.elapsed();
+
+    let mut output = vec![];
+    for &part in parts.iter().filter(|&&p| day.has_part(p)) {
+        let start = Instant::now();
+        let answer = match part {
+            1 => day.solver.part1(parsed.as_ref()),
+            _ => day.solver.part2(parsed.as_ref()),
+        };
+        output.push(PartRun {
+            part,
+            answer,
+            elapsed: start.elapsed(),
+        });
+    }
+
+    Ok(DayRun {
+        day: day.day,
+        parse_time,
+        parts: output,
+    })
+}
+
+/// Human friendly duration, picking the unit based on magnitude.
+pub fn format_duration(d: Duration) -> String {
+    let nanos = d.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.1}ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::get_day;
+    use crate::inputs::{load, InputKind};
+
+    #[test]
+    fn test_run_day() {
+        let input = load(2, &InputKind::Example(0)).unwrap();
+        let run = run_day(get_day(2).unwrap(), &input, &[1, 2]).unwrap();
+        let answers: Vec<_> = run.parts.iter().map(|p| p.answer.as_str()).collect();
+        assert_eq!(answers, vec!["15", "12"]);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(12)), "12ns");
+        assert_eq!(format_duration(Duration::from_micros(1500)), "1.5ms");
+        assert_eq!(format_duration(Duration::from_secs(2)), "2.00s");
+    }
+}
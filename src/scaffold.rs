@@ -0,0 +1,203 @@
+use crate::examples::sidecar_path;
+use crate::inputs::{example_paths, year_dir};
+use std::fmt;
+use std::fs::{read_to_string, write};
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const SRC_DIR: &str = "src";
+pub const TEMPLATE_FILE: &str = "template.rs";
+pub const LIB_FILE: &str = "lib.rs";
+
+#[derive(Debug)]
+pub enum ScaffoldError {
+    /// The day already has a module, and we never overwrite one.
+    Exists(PathBuf),
+    /// lib.rs no longer looks the way we expect, so we can't edit it.
+    Layout(&'static str),
+    Io(PathBuf, io::Error),
+}
+
+impl fmt::Display for ScaffoldError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScaffoldError::Exists(path) => write!(f, "{} already exists", path.display()),
+            ScaffoldError::Layout(what) => write!(f, "could not find {} in lib.rs", what),
+            ScaffoldError::Io(path, e) => write!(f, "could not access {}: {}", path.display(), e),
+        }
+    }
+}
+
+impl std::error::Error for ScaffoldError {}
+
+/// Fill in the template's `__DAY__` and `__INPUT__` placeholders.
+pub fn render(template: &str, day: u32, input_type: &str) -> String {
+    template
+        .replace("__DAY__", &day.to_string())
+        .replace("__INPUT__", input_type)
+}
+
+/// Index of the line to insert before so that `key` lands in order among
+/// the lines `key_of` recognises. Attributes stay attached to their item.
+fn insert_at<K: PartialOrd>(
+    lines: &[&str],
+    key: &K,
+    key_of: impl Fn(&str) -> Option<K>,
+) -> Option<usize> {
+    let keyed: Vec<(usize, K)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| Some((i, key_of(line)?)))
+        .collect();
+    let (last, _) = keyed.last()?;
+    let mut at = match keyed.iter().find(|(_, k)| k > key) {
+        Some(&(i, _)) => i,
+        None => return Some(last + 1),
+    };
+    while at > 0 && lines[at - 1].trim_start().starts_with("#[") {
+        at -= 1;
+    }
+    Some(at)
+}
+
+fn insert_line(text: &str, at: usize, line: &str) -> String {
+    let mut lines: Vec<&str> = text.lines().collect();
+    lines.insert(at, line);
+    lines.join("\n") + "\n"
+}
+
+/// Add `pub mod dayN;` to lib.rs, keeping the day modules sorted the way
+/// rustfmt sorts them.
+pub fn add_mod(lib: &str, day: u32) -> Result<String, ScaffoldError> {
+    let name = format!("day{}", day);
+    let lines: Vec<&str> = lib.lines().collect();
+    let key_of = |line: &str| {
+        line.strip_prefix("pub mod day")
+            .and_then(|s| s.strip_suffix(';'))
+            .map(|n| format!("day{}", n))
+    };
+    if lines
+        .iter()
+        .any(|line| key_of(line).as_ref() == Some(&name))
+    {
+        return Err(ScaffoldError::Exists(Path::new(SRC_DIR).join(LIB_FILE)));
+    }
+    let at = insert_at(&lines, &name, key_of).ok_or(ScaffoldError::Layout("the day modules"))?;
+    Ok(insert_line(lib, at, &format!("pub mod {};", name)))
+}
+
+/// Add the day to the `DAYS` registry in lib.rs, in day order.
+pub fn add_registry(lib: &str, day: u32) -> Result<String, ScaffoldError> {
+    let lines: Vec<&str> = lib.lines().collect();
+    let key_of = |line: &str| {
+        line.trim_start()
+            .strip_prefix("Day::new(")?
+            .split(',')
+            .next()?
+            .parse::<u32>()
+            .ok()
+    };
+    let at = insert_at(&lines, &day, key_of).ok_or(ScaffoldError::Layout("the DAYS registry"))?;
+    let entry = format!("    Day::new({}, &[1, 2], &day{}::Day{}),", day, day, day);
+    Ok(insert_line(lib, at, &entry))
+}
+
+fn read(path: &Path) -> Result<String, ScaffoldError> {
+    read_to_string(path).map_err(|e| ScaffoldError::Io(path.to_path_buf(), e))
+}
+
+fn write_new(path: &Path, contents: &str) -> Result<(), ScaffoldError> {
+    write(path, contents).map_err(|e| ScaffoldError::Io(path.to_path_buf(), e))
+}
+
+/// Expected answers for a new example, to be filled in from the puzzle.
+const SIDECAR: &str =
+    "# Answers to the example beside this file, once known\n#part1 = 0\n#part2 = 0\n";
+
+/// Create `src/dayN.rs` from the template, register it in lib.rs, and add
+/// an empty example with a sidecar for its answers. Returns every file
+/// touched.
+pub fn new_day(day: u32, input_type: &str) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let src = Path::new(SRC_DIR);
+    let module = src.join(format!("day{}.rs", day));
+    if module.exists() {
+        return Err(ScaffoldError::Exists(module));
+    }
+    let lib_path = src.join(LIB_FILE);
+    let lib = add_registry(&add_mod(&read(&lib_path)?, day)?, day)?;
+    let template = read(&src.join(TEMPLATE_FILE))?;
+
+    write_new(&module, &render(&template, day, input_type))?;
+    write_new(&lib_path, &lib)?;
+    let mut touched = vec![module, lib_path];
+
+    let example = match example_paths(day).into_iter().next() {
+        Some(example) => example,
+        None => {
+            let example = year_dir().join(format!("{:02}.txt", day));
+            write_new(&example, "")?;
+            touched.push(example.clone());
+            example
+        }
+    };
+    let sidecar = sidecar_path(&example);
+    if !sidecar.exists() {
+        write_new(&sidecar, SIDECAR)?;
+        touched.push(sidecar);
+    }
+    Ok(touched)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const LIB: &str = "\
+pub mod day1;
+pub mod day10;
+#[cfg(feature = \"day16\")]
+pub mod day16;
+pub mod day2;
+pub mod day9;
+
+registry! {
+    Day::new(1, &[1, 2], &day1::Day1),
+    Day::new(2, &[1, 2], &day2::Day2),
+    #[cfg(feature = \"day16\")]
+    Day::new(16, &[1], &day16::Day16).experimental(),
+}
+";
+
+    #[test]
+    fn test_render() {
+        let rendered = render(
+            "pub struct Day__DAY__;\ntype Input = __INPUT__;",
+            24,
+            "Vec<u8>",
+        );
+        assert_eq!(rendered, "pub struct Day24;\ntype Input = Vec<u8>;");
+    }
+
+    #[test]
+    fn test_sidecar() {
+        let expected = crate::examples::Expected::parse(SIDECAR).unwrap();
+        assert_eq!(expected, crate::examples::Expected::default());
+    }
+
+    #[test]
+    fn test_add_mod() {
+        let lib = add_mod(LIB, 11).unwrap();
+        assert!(lib.contains("pub mod day10;\npub mod day11;\n#[cfg(feature = \"day16\")]"));
+        let lib = add_mod(LIB, 24).unwrap();
+        assert!(lib.contains("pub mod day2;\npub mod day24;\npub mod day9;"));
+        assert!(matches!(add_mod(LIB, 10), Err(ScaffoldError::Exists(_))));
+    }
+
+    #[test]
+    fn test_add_registry() {
+        let lib = add_registry(LIB, 12).unwrap();
+        assert!(lib.contains("Day2),\n    Day::new(12, &[1, 2], &day12::Day12),\n    #[cfg"));
+        let lib = add_registry(LIB, 24).unwrap();
+        assert!(lib.contains("experimental(),\n    Day::new(24, &[1, 2], &day24::Day24),\n}"));
+    }
+}
@@ -0,0 +1,255 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+/// A graph given by the nodes one step away from each node.
+pub trait Connected {
+    type Node: Clone + Eq + Hash;
+
+    fn neighbours(&self, node: &Self::Node) -> Vec<Self::Node>;
+}
+
+/// A graph where each step has a cost.
+pub trait Weighted {
+    type Node: Clone + Eq + Hash;
+
+    /// The nodes one step away from `node` and what it costs to get there.
+    fn edges(&self, node: &Self::Node) -> Vec<(Self::Node, u64)>;
+}
+
+/// Everything a search reached: how far away each node is, and the node
+/// before it on a shortest path.
+#[derive(Clone, Debug)]
+pub struct Paths<N> {
+    distance: HashMap<N, u64>,
+    previous: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> Paths<N> {
+    fn new() -> Self {
+        Self {
+            distance: HashMap::new(),
+            previous: HashMap::new(),
+        }
+    }
+
+    /// Cost of the cheapest way to `node`, or None if it wasn't reached.
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.distance.get(node).copied()
+    }
+
+    /// Every node reached, with its distance.
+    pub fn reached(&self) -> impl Iterator<Item = (&N, u64)> {
+        self.distance.iter().map(|(node, &d)| (node, d))
+    }
+
+    /// A shortest path from a start to `node`, both included, worked back
+    /// from the predecessors.
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        self.distance.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(prev) = self.previous.get(path.last().unwrap()) {
+            path.push(prev.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth first from every one of `starts` at once, stopping early once
+/// `done` is true of a node.
+fn bfs_until<G: Connected>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+    mut done: impl FnMut(&G::Node) -> bool,
+) -> Paths<G::Node> {
+    let mut paths = Paths::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if paths.distance.insert(start.clone(), 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+    while let Some(node) = queue.pop_front() {
+        if done(&node) {
+            break;
+        }
+        let next_distance = paths.distance[&node] + 1;
+        for next in graph.neighbours(&node) {
+            if !paths.distance.contains_key(&next) {
+                paths.distance.insert(next.clone(), next_distance);
+                paths.previous.insert(next.clone(), node.clone());
+                queue.push_back(next);
+            }
+        }
+    }
+    paths
+}
+
+/// Shortest paths by number of steps from `start` to everywhere reachable.
+pub fn bfs<G: Connected>(graph: &G, start: G::Node) -> Paths<G::Node> {
+    bfs_until(graph, [start], |_| false)
+}
+
+/// Like `bfs`, where the distance to each node is from whichever start is
+/// nearest.
+pub fn multi_bfs<G: Connected>(
+    graph: &G,
+    starts: impl IntoIterator<Item = G::Node>,
+) -> Paths<G::Node> {
+    bfs_until(graph, starts, |_| false)
+}
+
+/// Fewest steps from `start` to `goal`, both included in the path.
+pub fn shortest_path<G: Connected>(
+    graph: &G,
+    start: G::Node,
+    goal: &G::Node,
+) -> Option<Vec<G::Node>> {
+    bfs_until(graph, [start], |node| node == goal).path(goal)
+}
+
+/// Best first search, with `estimate` giving a lower bound on the cost left
+/// from a node. Stops at the first node `done` is true of.
+fn best_first<G: Weighted>(
+    graph: &G,
+    start: G::Node,
+    estimate: impl Fn(&G::Node) -> u64,
+    mut done: impl FnMut(&G::Node) -> bool,
+) -> (Paths<G::Node>, Option<G::Node>) {
+    let mut paths = Paths::new();
+    // The heap holds indices into `nodes`, so nodes needn't be `Ord`
+    let mut nodes = vec![start.clone()];
+    let mut heap = BinaryHeap::from([Reverse((estimate(&start), 0, 0))]);
+    paths.distance.insert(start, 0);
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        let node = nodes[i].clone();
+        if cost > paths.distance[&node] {
+            // Already found a cheaper way here
+            continue;
+        }
+        if done(&node) {
+            return (paths, Some(node));
+        }
+        for (next, step) in graph.edges(&node) {
+            let next_cost = cost + step;
+            if paths.distance.get(&next).is_some_and(|&d| d <= next_cost) {
+                continue;
+            }
+            paths.distance.insert(next.clone(), next_cost);
+            paths.previous.insert(next.clone(), node.clone());
+            heap.push(Reverse((
+                next_cost + estimate(&next),
+                next_cost,
+                nodes.len(),
+            )));
+            nodes.push(next);
+        }
+    }
+    (paths, None)
+}
+
+/// Cheapest paths from `start` to everywhere reachable.
+pub fn dijkstra<G: Weighted>(graph: &G, start: G::Node) -> Paths<G::Node> {
+    best_first(graph, start, |_| 0, |_| false).0
+}
+
+/// Cheapest path from `start` to a node `is_goal` accepts, and its cost.
+/// `heuristic` must never overestimate the cost left, or the path found
+/// may not be the cheapest.
+pub fn astar<G: Weighted>(
+    graph: &G,
+    start: G::Node,
+    mut is_goal: impl FnMut(&G::Node) -> bool,
+    heuristic: impl Fn(&G::Node) -> u64,
+) -> Option<(Vec<G::Node>, u64)> {
+    let (paths, goal) = best_first(graph, start, heuristic, &mut is_goal);
+    let goal = goal?;
+    Some((paths.path(&goal)?, paths.distance(&goal)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::geom::Point2;
+    use crate::grid::Grid;
+
+    /// Open squares of a maze, where moving costs the digit moved onto.
+    struct Maze(Grid<char>);
+
+    impl Maze {
+        fn new(text: &str) -> Self {
+            Self(Grid::from_str(0, text, "a maze", Some).unwrap())
+        }
+    }
+
+    impl Connected for Maze {
+        type Node = Point2<usize>;
+
+        fn neighbours(&self, node: &Self::Node) -> Vec<Self::Node> {
+            self.0
+                .neighbours4(*node)
+                .filter(|&p| self.0[p] != '#')
+                .collect()
+        }
+    }
+
+    impl Weighted for Maze {
+        type Node = Point2<usize>;
+
+        fn edges(&self, node: &Self::Node) -> Vec<(Self::Node, u64)> {
+            Connected::neighbours(self, node)
+                .into_iter()
+                .map(|p| (p, self.0[p].to_digit(10).unwrap_or(1) as u64))
+                .collect()
+        }
+    }
+
+    const MAZE: &str = "\
+.....
+.###.
+..9..
+";
+
+    #[test]
+    fn test_bfs() {
+        let maze = Maze::new(MAZE);
+        let start = Point2::new(0, 2);
+        let paths = bfs(&maze, start);
+        assert_eq!(paths.distance(&Point2::new(4, 2)), Some(4));
+        assert_eq!(paths.distance(&Point2::new(1, 1)), None);
+        assert_eq!(paths.reached().count(), 12);
+
+        let path = shortest_path(&maze, start, &Point2::new(4, 2)).unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path[0], start);
+        assert!(path.windows(2).all(|w| w[0].manhattan(w[1]) == 1));
+        assert_eq!(shortest_path(&maze, start, &Point2::new(2, 1)), None);
+
+        let paths = multi_bfs(&maze, [start, Point2::new(4, 0)]);
+        assert_eq!(paths.distance(&Point2::new(2, 0)), Some(2));
+        assert_eq!(
+            paths.path(&Point2::new(4, 0)),
+            Some(vec![Point2::new(4, 0)])
+        );
+    }
+
+    #[test]
+    fn test_weighted() {
+        let maze = Maze::new(MAZE);
+        let start = Point2::new(0, 2);
+        let goal = Point2::new(4, 2);
+        // Through the 9 is fewer steps, but around the top is cheaper
+        assert_eq!(dijkstra(&maze, start).distance(&goal), Some(8));
+
+        let heuristic = |p: &Point2<usize>| p.manhattan(goal) as u64;
+        let (path, cost) = astar(&maze, start, |&p| p == goal, heuristic).unwrap();
+        assert_eq!(cost, 8);
+        assert_eq!(path.len(), 9);
+        assert_eq!(path.last(), Some(&goal));
+        assert_eq!(
+            astar(&maze, start, |&p| p == Point2::new(1, 1), |_| 0),
+            None
+        );
+    }
+}
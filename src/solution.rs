@@ -0,0 +1,123 @@
+use crate::parse::ParseError;
+use crate::viz::Viz;
+use std::any::{type_name, Any};
+use std::fmt::Display;
+
+/// A single day's puzzle: how to parse the input and how to answer both parts.
+pub trait Solution {
+    type Input;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Answer1;
+    fn part2(input: &Self::Input) -> Self::Answer2;
+
+    /// Replay the solution frame by frame into `viz`. Returns false for days
+    /// with nothing to show, or when the user quits before the end.
+    fn visualize(_input: &Self::Input, _viz: &mut Viz) -> bool {
+        false
+    }
+}
+
+/// Type erased view of a `Solution` so days with different input and answer
+/// types can live side by side in the registry.
+pub trait Solver: Sync {
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError>;
+    fn part1(&self, input: &dyn Any) -> String;
+    fn part2(&self, input: &dyn Any) -> String;
+    fn visualize(&self, input: &dyn Any, viz: &mut Viz) -> bool;
+    fn input_type(&self) -> &'static str;
+}
+
+impl<S> Solver for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn part1(&self, input: &dyn Any) -> String {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input was not parsed by this solver");
+        S::part1(input).to_string()
+    }
+
+    fn part2(&self, input: &dyn Any) -> String {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input was not parsed by this solver");
+        S::part2(input).to_string()
+    }
+
+    fn visualize(&self, input: &dyn Any, viz: &mut Viz) -> bool {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .expect("input was not parsed by this solver");
+        S::visualize(input, viz)
+    }
+
+    fn input_type(&self) -> &'static str {
+        type_name::<S::Input>()
+    }
+}
+
+/// Whether a day can be trusted to finish with the right answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Stable,
+    /// Only built with the day's cargo feature, and skipped by `run --all`
+    /// and `check` unless asked for.
+    Experimental,
+}
+
+/// Registry entry for one day.
+pub struct Day {
+    pub day: u32,
+    pub parts: &'static [u8],
+    pub solver: &'static dyn Solver,
+    pub status: Status,
+}
+
+impl Day {
+    pub const fn new(day: u32, parts: &'static [u8], solver: &'static dyn Solver) -> Self {
+        Self {
+            day,
+            parts,
+            solver,
+            status: Status::Stable,
+        }
+    }
+
+    pub const fn experimental(self) -> Self {
+        Self {
+            status: Status::Experimental,
+            ..self
+        }
+    }
+
+    pub fn is_stable(&self) -> bool {
+        self.status == Status::Stable
+    }
+
+    pub fn has_part(&self, part: u8) -> bool {
+        self.parts.contains(&part)
+    }
+
+    /// Parse `input` and run `part`, returning `None` if this day has no
+    /// such part.
+    pub fn run(&self, part: u8, input: &str) -> Result<Option<String>, ParseError> {
+        if !self.has_part(part) {
+            return Ok(None);
+        }
+        let parsed = self.solver.parse(input)?;
+        Ok(match part {
+            1 => Some(self.solver.part1(parsed.as_ref())),
+            2 => Some(self.solver.part2(parsed.as_ref())),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,278 @@
+use crate::geom::Point2;
+use std::collections::{BTreeMap, HashMap};
+
+/// Smallest rectangle holding a set of points. Both corners are included.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Point2<i64>,
+    pub max: Point2<i64>,
+}
+
+impl Bounds {
+    /// The rectangle with corners `a` and `b`, whichever way round they are.
+    pub fn new(a: Point2<i64>, b: Point2<i64>) -> Self {
+        Self {
+            min: Point2::new(a.x.min(b.x), a.y.min(b.y)),
+            max: Point2::new(a.x.max(b.x), a.y.max(b.y)),
+        }
+    }
+
+    pub fn point(p: Point2<i64>) -> Self {
+        Self { min: p, max: p }
+    }
+
+    pub fn width(&self) -> i64 {
+        self.max.x - self.min.x + 1
+    }
+
+    pub fn height(&self) -> i64 {
+        self.max.y - self.min.y + 1
+    }
+
+    pub fn area(&self) -> i64 {
+        self.width() * self.height()
+    }
+
+    pub fn contains(&self, p: Point2<i64>) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    /// Grow to take in `p`.
+    pub fn extend(&mut self, p: Point2<i64>) {
+        self.min = Point2::new(self.min.x.min(p.x), self.min.y.min(p.y));
+        self.max = Point2::new(self.max.x.max(p.x), self.max.y.max(p.y));
+    }
+
+    /// Every point inside, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point2<i64>> {
+        let Bounds { min, max } = *self;
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point2::new(x, y)))
+    }
+}
+
+/// Cells scattered over an unbounded plane, for simulations that grow in
+/// any direction. Keeps track of its bounding box as it goes. Without a
+/// value type it is a set of points.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T = ()> {
+    cells: HashMap<Point2<i64>, T>,
+    // How many cells are in each column and row, so the bounds can shrink
+    // without a rescan
+    xs: BTreeMap<i64, usize>,
+    ys: BTreeMap<i64, usize>,
+}
+
+fn count_in(axis: &mut BTreeMap<i64, usize>, at: i64) {
+    *axis.entry(at).or_insert(0) += 1;
+}
+
+fn count_out(axis: &mut BTreeMap<i64, usize>, at: i64) {
+    if let Some(count) = axis.get_mut(&at) {
+        *count -= 1;
+        if *count == 0 {
+            axis.remove(&at);
+        }
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self {
+            cells: HashMap::new(),
+            xs: BTreeMap::new(),
+            ys: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Smallest rectangle holding every cell, or None when there are none.
+    pub fn bounds(&self) -> Option<Bounds> {
+        let (&min_x, _) = self.xs.first_key_value()?;
+        let (&max_x, _) = self.xs.last_key_value()?;
+        let (&min_y, _) = self.ys.first_key_value()?;
+        let (&max_y, _) = self.ys.last_key_value()?;
+        Some(Bounds {
+            min: Point2::new(min_x, min_y),
+            max: Point2::new(max_x, max_y),
+        })
+    }
+
+    pub fn contains(&self, p: Point2<i64>) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    pub fn get(&self, p: Point2<i64>) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    /// Set the cell at `p`, returning what was there.
+    pub fn insert(&mut self, p: Point2<i64>, value: T) -> Option<T> {
+        let old = self.cells.insert(p, value);
+        if old.is_none() {
+            count_in(&mut self.xs, p.x);
+            count_in(&mut self.ys, p.y);
+        }
+        old
+    }
+
+    /// Clear the cell at `p`, returning what was there.
+    pub fn remove(&mut self, p: Point2<i64>) -> Option<T> {
+        let value = self.cells.remove(&p)?;
+        count_out(&mut self.xs, p.x);
+        count_out(&mut self.ys, p.y);
+        Some(value)
+    }
+
+    /// Every cell, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2<i64>, &T)> {
+        self.cells.iter().map(|(&p, value)| (p, value))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point2<i64>> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// The cells inside `area`, in no particular order.
+    pub fn range(&self, area: Bounds) -> Box<dyn Iterator<Item = (Point2<i64>, &T)> + '_> {
+        // Look up each point when the area is small, otherwise filter
+        if area.area() < self.cells.len() as i64 {
+            Box::new(
+                area.points()
+                    .filter_map(move |p| self.cells.get(&p).map(|value| (p, value))),
+            )
+        } else {
+            Box::new(self.iter().filter(move |&(p, _)| area.contains(p)))
+        }
+    }
+
+    /// A copy of just the cells inside `area`.
+    pub fn region(&self, area: Bounds) -> Self
+    where
+        T: Clone,
+    {
+        self.range(area)
+            .map(|(p, value)| (p, value.clone()))
+            .collect()
+    }
+
+    /// `area` as text, one line per row, with `glyph` choosing each
+    /// character from the cell there, if any.
+    pub fn render(&self, area: Bounds, glyph: impl Fn(Option<&T>) -> char) -> String {
+        let mut text = String::new();
+        for y in area.min.y..=area.max.y {
+            text.extend((area.min.x..=area.max.x).map(|x| glyph(self.get(Point2::new(x, y)))));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl<T> FromIterator<(Point2<i64>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point2<i64>, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point2<i64>, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point2<i64>, T)>>(&mut self, iter: I) {
+        for (p, value) in iter {
+            self.insert(p, value);
+        }
+    }
+}
+
+impl FromIterator<Point2<i64>> for SparseGrid {
+    fn from_iter<I: IntoIterator<Item = Point2<i64>>>(iter: I) -> Self {
+        iter.into_iter().map(|p| (p, ())).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn elves() -> SparseGrid {
+        [(2, 1), (-1, 0), (0, 3), (1, 1)]
+            .into_iter()
+            .map(Point2::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_bounds() {
+        let mut grid = elves();
+        let bounds = grid.bounds().unwrap();
+        assert_eq!(bounds, Bounds::new(Point2::new(2, 0), Point2::new(-1, 3)));
+        assert_eq!((bounds.width(), bounds.height(), bounds.area()), (4, 4, 16));
+
+        // Only the minimum moved, which a max-first update would miss
+        grid.insert(Point2::new(-3, -2), ());
+        assert_eq!(grid.bounds().unwrap().min, Point2::new(-3, -2));
+
+        grid.remove(Point2::new(-3, -2));
+        grid.remove(Point2::new(1, 1));
+        assert_eq!(grid.bounds(), Some(bounds));
+        grid.remove(Point2::new(0, 3));
+        assert_eq!(grid.bounds().unwrap().max, Point2::new(2, 1));
+        assert_eq!(grid.remove(Point2::new(0, 3)), None);
+        grid.remove(Point2::new(2, 1));
+        grid.remove(Point2::new(-1, 0));
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn test_shared_edge() {
+        // Two cells on the top edge; the bounds hold until both are gone,
+        // and overwriting a cell doesn't count it twice
+        let mut grid = elves();
+        grid.insert(Point2::new(1, 1), ());
+        grid.remove(Point2::new(1, 1));
+        assert_eq!(grid.bounds().unwrap().max, Point2::new(2, 3));
+        grid.insert(Point2::new(0, -1), ());
+        grid.insert(Point2::new(2, -1), ());
+        grid.remove(Point2::new(0, -1));
+        assert_eq!(grid.bounds().unwrap().min, Point2::new(-1, -1));
+        grid.remove(Point2::new(2, -1));
+        assert_eq!(grid.bounds().unwrap().min, Point2::new(-1, 0));
+        assert_eq!(grid.bounds().unwrap().max, Point2::new(2, 3));
+    }
+
+    #[test]
+    fn test_range() {
+        let grid = elves();
+        let area = Bounds::new(Point2::new(0, 0), Point2::new(2, 1));
+        let mut found: Vec<_> = grid.range(area).map(|(p, _)| p).collect();
+        found.sort();
+        assert_eq!(found, vec![Point2::new(1, 1), Point2::new(2, 1)]);
+        let single = Bounds::point(Point2::new(0, 3));
+        assert_eq!(grid.range(single).count(), 1);
+        assert_eq!(grid.region(area).len(), 2);
+        let region = grid.region(area).bounds().unwrap();
+        assert_eq!(region, Bounds::new(Point2::new(1, 1), Point2::new(2, 1)));
+    }
+
+    #[test]
+    fn test_render() {
+        let mut grid: SparseGrid<char> = SparseGrid::new();
+        grid.insert(Point2::new(500, 0), '+');
+        grid.insert(Point2::new(499, 2), '#');
+        let text = grid.render(grid.bounds().unwrap(), |c| c.copied().unwrap_or('.'));
+        assert_eq!(text, ".+\n..\n#.\n");
+    }
+}
@@ -0,0 +1,251 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+/// Comparison against a watched value, written like `rock>=2022`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Predicate {
+    name: String,
+    op: Op,
+    value: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Ge,
+    Gt,
+}
+
+const OPS: [(&str, Op); 6] = [
+    ("<=", Op::Le),
+    (">=", Op::Ge),
+    ("==", Op::Eq),
+    ("!=", Op::Ne),
+    ("<", Op::Lt),
+    (">", Op::Gt),
+];
+
+impl Predicate {
+    /// True if `watch` has our value and it compares the right way. A value
+    /// the simulation doesn't report never matches.
+    pub fn matches(&self, watch: &[(&str, i64)]) -> bool {
+        let Some(&(_, actual)) = watch.iter().find(|(name, _)| *name == self.name) else {
+            return false;
+        };
+        match self.op {
+            Op::Lt => actual < self.value,
+            Op::Le => actual <= self.value,
+            Op::Eq => actual == self.value,
+            Op::Ne => actual != self.value,
+            Op::Ge => actual >= self.value,
+            Op::Gt => actual > self.value,
+        }
+    }
+}
+
+impl FromStr for Predicate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        for (text, op) in OPS {
+            if let Some((name, value)) = s.split_once(text) {
+                if name.is_empty() {
+                    break;
+                }
+                let value = value
+                    .parse()
+                    .map_err(|_| format!("`{}` is not a number", value))?;
+                return Ok(Self {
+                    name: name.to_string(),
+                    op,
+                    value,
+                });
+            }
+        }
+        Err(format!(
+            "expected <name><op><number>, like round==10, not `{}`",
+            s
+        ))
+    }
+}
+
+impl fmt::Display for Predicate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = OPS.iter().find(|(_, op)| *op == self.op).unwrap().0;
+        write!(f, "{}{}{}", self.name, op, self.value)
+    }
+}
+
+/// What the stepper does when the next tick comes round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Stop and ask what to do
+    Pause,
+    /// Never stop again
+    Continue,
+    /// Run without stopping until this tick number
+    RunTo(u64),
+    /// Run without stopping until the predicate holds
+    Until(Predicate),
+    Quit,
+}
+
+const HELP: &str = "\
+  <enter>, s    step one tick
+  c             continue to the end
+  t N           run to tick N
+  u PRED        run until PRED holds, e.g. u round==10
+  q             quit";
+
+/// Pauses a simulation between ticks and asks what to do next. Simulations
+/// call `tick` through `Viz::tick` once per step, along with a few named
+/// values worth watching.
+pub struct Stepper<'a> {
+    mode: Mode,
+    tick: u64,
+    input: Box<dyn BufRead + 'a>,
+    output: Box<dyn Write + 'a>,
+}
+
+impl Stepper<'static> {
+    /// A stepper driven from the terminal.
+    pub fn stdio(mode: Mode) -> Self {
+        Self::new(mode, io::stdin().lock(), io::stdout())
+    }
+}
+
+impl<'a> Stepper<'a> {
+    pub fn new(mode: Mode, input: impl BufRead + 'a, output: impl Write + 'a) -> Self {
+        Self {
+            mode,
+            tick: 0,
+            input: Box::new(input),
+            output: Box::new(output),
+        }
+    }
+
+    /// Number of ticks seen so far.
+    pub fn ticks(&self) -> u64 {
+        self.tick
+    }
+
+    /// Start of a tick. Returns false once the user has asked to quit.
+    pub fn tick(&mut self, watch: &[(&str, i64)]) -> bool {
+        let tick = self.tick;
+        self.tick += 1;
+        let pause = match &self.mode {
+            Mode::Pause => true,
+            Mode::Continue => false,
+            Mode::RunTo(n) => tick >= *n,
+            Mode::Until(predicate) => predicate.matches(watch),
+            Mode::Quit => return false,
+        };
+        if pause {
+            // A broken terminal shouldn't take the simulation down with it
+            self.mode = self.prompt(tick, watch).unwrap_or(Mode::Continue);
+        }
+        self.mode != Mode::Quit
+    }
+
+    fn prompt(&mut self, tick: u64, watch: &[(&str, i64)]) -> io::Result<Mode> {
+        loop {
+            write!(self.output, "tick {}", tick)?;
+            for (name, value) in watch {
+                write!(self.output, " {}={}", name, value)?;
+            }
+            write!(self.output, " > ")?;
+            self.output.flush()?;
+
+            let mut line = String::new();
+            if self.input.read_line(&mut line)? == 0 {
+                // Nobody to ask, so just finish
+                return Ok(Mode::Continue);
+            }
+            let mut words = line.split_whitespace();
+            let mode = match (words.next(), words.next()) {
+                (None | Some("s"), _) => Ok(Mode::Pause),
+                (Some("c"), _) => Ok(Mode::Continue),
+                (Some("q"), _) => Ok(Mode::Quit),
+                (Some("t"), Some(n)) => n
+                    .parse()
+                    .map(Mode::RunTo)
+                    .map_err(|_| format!("`{}` is not a tick number", n)),
+                (Some("u"), Some(_)) => {
+                    let predicate = line.trim_start().strip_prefix('u').unwrap_or_default();
+                    predicate.parse().map(Mode::Until)
+                }
+                _ => Err(String::from("unknown command")),
+            };
+            match mode {
+                Ok(mode) => return Ok(mode),
+                Err(e) => writeln!(self.output, "{}\n{}", e, HELP)?,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(mode: Mode, keys: &str, ticks: i64) -> (Vec<i64>, String) {
+        let mut output = vec![];
+        let mut seen = vec![];
+        {
+            let mut stepper = Stepper::new(mode, keys.as_bytes(), &mut output);
+            for round in 0..ticks {
+                if !stepper.tick(&[("round", round)]) {
+                    break;
+                }
+                seen.push(round);
+            }
+        }
+        (seen, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_predicate() {
+        let p: Predicate = "rock >= 2022".parse().unwrap();
+        assert_eq!(p.to_string(), "rock>=2022");
+        assert!(p.matches(&[("height", 1), ("rock", 2022)]));
+        assert!(!p.matches(&[("rock", 2021)]));
+        assert!(!p.matches(&[("height", 3000)]));
+        assert!("round=10".parse::<Predicate>().is_err());
+        assert!("==10".parse::<Predicate>().is_err());
+        assert!("round<x".parse::<Predicate>().is_err());
+    }
+
+    #[test]
+    fn test_step_and_quit() {
+        let (seen, output) = run(Mode::Pause, "\ns\nq\n", 10);
+        assert_eq!(seen, vec![0, 1]);
+        assert_eq!(output.matches(" > ").count(), 3);
+        assert!(output.starts_with("tick 0 round=0 > "));
+    }
+
+    #[test]
+    fn test_run_to() {
+        let (seen, output) = run(Mode::RunTo(5), "t 8\nbogus\nc\n", 10);
+        assert_eq!(seen.len(), 10);
+        assert!(output.starts_with("tick 5 round=5 > tick 8 round=8 > unknown command"));
+    }
+
+    #[test]
+    fn test_until() {
+        let until = Mode::Until("round==3".parse().unwrap());
+        let (seen, output) = run(until, "u round > 6\nq\n", 10);
+        assert_eq!(seen, vec![0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(output, "tick 3 round=3 > tick 7 round=7 > ");
+    }
+
+    #[test]
+    fn test_no_input() {
+        let (seen, _) = run(Mode::Pause, "", 10);
+        assert_eq!(seen.len(), 10);
+    }
+}
@@ -1,64 +1,85 @@
-use std::collections::{HashMap, HashSet, VecDeque};
-use scan_fmt::scan_fmt;
-use std::io::Write;
+use crate::parse::{Lines, ParseError};
+use crate::solution::Solution;
+// For maps, simulations and formatted lines:
+//use crate::grid::Grid;
+//use crate::viz::{Frame, Viz};
+//use scan_fmt::scan_fmt;
 
-type Input = usize;
+type Input = __INPUT__;
 
-fn print(board: &Vec<Vec<usize>>, breakpoint: bool) {
-    for x in board {
-        for y in x {
-            print!(".");
-        }
-        println!();
-    }
-    println!();
-
-    if breakpoint {
-        // Get user input to continue to next step
-        std::io::stdout().flush();
-        let mut asdf = String::new();
-        std::io::stdin().read_line(&mut asdf);
-    }
-}
+//fn draw(board: &Grid<char>) -> Frame {
+//    let mut frame = Frame::new(board.width(), board.height(), '.');
+//    for (p, &c) in board.iter() {
+//        frame.put(p.x, p.y, c);
+//    }
+//    frame
+//}
 
-#[aoc_generator(dayN)]
-pub fn load_input(input: &str) -> Vec<Input> {
+pub fn load_input(input: &str) -> Result<Vec<Input>, ParseError> {
+    // For a map, one cell per character:
+    //Grid::from_str(__DAY__, input, "`.` or `#`", |c| matches!(c, '.' | '#').then_some(c))
     let mut output = vec![];
-    for line in input.lines() {
-        output.push(line.parse::<Input>().unwrap());
-        //output.push(scan_fmt!(line, "{} {}", char, char).unwrap());
+    for line in Lines::new(__DAY__, input) {
+        output.push(line.parse::<Input>(line.text, "a number")?);
+        //output.push(scan_fmt!(line.text, "{} {}", char, char).map_err(|_| line.scan_error("{} {}"))?);
     }
-    output
+    Ok(output)
 }
 
-#[aoc(dayN, part1)]
 pub fn part1(input: &[Input]) -> usize {
-
-    0
+    input.len()
 }
 
-#[aoc(dayN, part2)]
 pub fn part2(input: &[Input]) -> usize {
+    input.len()
+}
+
+pub struct Day__DAY__;
+
+impl Solution for Day__DAY__ {
+    type Input = Vec<Input>;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        load_input(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Answer1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Answer2 {
+        part2(input)
+    }
 
-    0
+    //fn visualize(input: &Self::Input, viz: &mut Viz) -> bool {
+    //    for tick in 0.. {
+    //        viz.show(|| draw(&board));
+    //        if !viz.tick(&[("tick", tick)]) {
+    //            break;
+    //        }
+    //    }
+    //    true
+    //}
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::fs::read_to_string;
+    use crate::inputs::{load, InputKind};
 
     #[test]
     fn test_part1() {
-        let input = read_to_string("input/2022/XXa.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(__DAY__, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part1(&input), 0);
     }
 
     #[test]
     fn test_part2() {
-        let input = read_to_string("input/2022/XXa.txt").unwrap();
-        let input = load_input(&input);
+        let input = load(__DAY__, &InputKind::Example(0)).unwrap();
+        let input = load_input(&input).unwrap();
         assert_eq!(part2(&input), 0);
     }
 }
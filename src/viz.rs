@@ -0,0 +1,423 @@
+use crate::stepper::Stepper;
+use std::fmt::Write as _;
+use std::fs::{create_dir_all, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+use termion::{clear, color, cursor};
+
+/// Colour of a cell. `Default` leaves it up to the backend.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Colour {
+    Default,
+    Rgb(u8, u8, u8),
+}
+
+impl Colour {
+    pub const RED: Colour = Colour::Rgb(220, 50, 47);
+    pub const GREEN: Colour = Colour::Rgb(133, 153, 0);
+    pub const YELLOW: Colour = Colour::Rgb(181, 137, 0);
+    pub const BLUE: Colour = Colour::Rgb(38, 139, 210);
+    pub const GREY: Colour = Colour::Rgb(88, 110, 117);
+
+    /// Pixel colour for an image. Cells with no colour of their own are
+    /// black if empty and white otherwise.
+    fn rgb(&self, glyph: char) -> [u8; 3] {
+        match self {
+            Colour::Rgb(r, g, b) => [*r, *g, *b],
+            Colour::Default if glyph == ' ' || glyph == '.' => [0, 0, 0],
+            Colour::Default => [255, 255, 255],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub colour: Colour,
+}
+
+impl Cell {
+    pub const fn new(glyph: char) -> Self {
+        Self {
+            glyph,
+            colour: Colour::Default,
+        }
+    }
+
+    pub const fn coloured(glyph: char, colour: Colour) -> Self {
+        Self { glyph, colour }
+    }
+}
+
+/// One picture of a simulation: a grid of cells plus a few lines of text
+/// to show above it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    pub caption: Vec<String>,
+}
+
+impl Frame {
+    /// A `width` by `height` frame filled with `fill`.
+    pub fn new(width: usize, height: usize, fill: char) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::new(fill); width * height],
+            caption: vec![],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Cell> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    /// Set the cell at column `x`, row `y`. Anything outside the frame is
+    /// clipped rather than an error, so callers can draw without checking.
+    pub fn set(&mut self, x: usize, y: usize, cell: Cell) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = cell;
+        }
+    }
+
+    pub fn put(&mut self, x: usize, y: usize, glyph: char) {
+        self.set(x, y, Cell::new(glyph));
+    }
+
+    pub fn caption(mut self, line: impl Into<String>) -> Self {
+        self.caption.push(line.into());
+        self
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    /// The caption and grid as plain text, one line per row.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for line in &self.caption {
+            writeln!(text, "{}", line).unwrap();
+        }
+        for row in self.rows() {
+            text.extend(row.iter().map(|c| c.glyph));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Binary PPM image with each cell drawn as a `scale` pixel square.
+    pub fn write_ppm(&self, out: &mut impl Write, scale: usize) -> io::Result<()> {
+        let scale = scale.max(1);
+        write!(
+            out,
+            "P6\n{} {}\n255\n",
+            self.width * scale,
+            self.height * scale
+        )?;
+        for row in self.rows() {
+            let line: Vec<u8> = row
+                .iter()
+                .flat_map(|c| c.colour.rgb(c.glyph).repeat(scale))
+                .collect();
+            for _ in 0..scale {
+                out.write_all(&line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Somewhere to send frames.
+pub trait Backend {
+    fn show(&mut self, frame: &Frame) -> io::Result<()>;
+
+    /// Called once after the last frame.
+    fn finish(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Redraws each frame in place on a terminal, pausing `delay` between them.
+pub struct Terminal<W: Write> {
+    out: W,
+    delay: Duration,
+    started: bool,
+}
+
+impl Terminal<io::Stdout> {
+    pub fn stdout(delay: Duration) -> Self {
+        Self::new(io::stdout(), delay)
+    }
+}
+
+impl<W: Write> Terminal<W> {
+    pub fn new(out: W, delay: Duration) -> Self {
+        Self {
+            out,
+            delay,
+            started: false,
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Backend for Terminal<W> {
+    fn show(&mut self, frame: &Frame) -> io::Result<()> {
+        if !self.started {
+            write!(self.out, "{}{}", clear::All, cursor::Hide)?;
+            self.started = true;
+        }
+        write!(self.out, "{}", cursor::Goto(1, 1))?;
+        for line in &frame.caption {
+            write!(self.out, "{}{}\r\n", line, clear::UntilNewline)?;
+        }
+        for row in frame.rows() {
+            let mut current = Colour::Default;
+            for cell in row {
+                if cell.colour != current {
+                    match cell.colour {
+                        Colour::Rgb(r, g, b) => {
+                            write!(self.out, "{}", color::Fg(color::Rgb(r, g, b)))?
+                        }
+                        Colour::Default => write!(self.out, "{}", color::Fg(color::Reset))?,
+                    }
+                    current = cell.colour;
+                }
+                write!(self.out, "{}", cell.glyph)?;
+            }
+            if current != Colour::Default {
+                write!(self.out, "{}", color::Fg(color::Reset))?;
+            }
+            write!(self.out, "{}\r\n", clear::UntilNewline)?;
+        }
+        write!(self.out, "{}", clear::AfterCursor)?;
+        self.out.flush()?;
+        thread::sleep(self.delay);
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        if self.started {
+            write!(self.out, "{}", cursor::Show)?;
+            self.out.flush()?;
+            self.started = false;
+        }
+        Ok(())
+    }
+}
+
+/// File format for `Headless` frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    /// PPM image, with cells this many pixels across
+    Ppm(usize),
+}
+
+/// Writes every frame to its own numbered file, so nothing needs a TTY.
+pub struct Headless {
+    dir: PathBuf,
+    format: Format,
+    count: usize,
+}
+
+impl Headless {
+    pub fn new(dir: &Path, format: Format) -> io::Result<Self> {
+        create_dir_all(dir)?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            format,
+            count: 0,
+        })
+    }
+
+    /// Number of frames written so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Backend for Headless {
+    fn show(&mut self, frame: &Frame) -> io::Result<()> {
+        let extension = match self.format {
+            Format::Text => "txt",
+            Format::Ppm(_) => "ppm",
+        };
+        let path = self
+            .dir
+            .join(format!("frame{:05}.{}", self.count, extension));
+        let mut out = BufWriter::new(File::create(path)?);
+        match self.format {
+            Format::Text => out.write_all(frame.to_text().as_bytes())?,
+            Format::Ppm(scale) => frame.write_ppm(&mut out, scale)?,
+        }
+        out.flush()?;
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// Where a simulation sends its frames, and optionally a stepper to pause
+/// it between ticks. Off by default, in which case frames are never even
+/// built, so solving doesn't pay for drawing.
+#[derive(Default)]
+pub struct Viz<'a> {
+    backend: Option<&'a mut dyn Backend>,
+    stepper: Option<Stepper<'a>>,
+    error: Option<io::Error>,
+    quit: bool,
+}
+
+impl<'a> Viz<'a> {
+    pub fn off() -> Self {
+        Self::default()
+    }
+
+    pub fn new(backend: &'a mut dyn Backend) -> Self {
+        Self {
+            backend: Some(backend),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_stepper(mut self, stepper: Stepper<'a>) -> Self {
+        self.stepper = Some(stepper);
+        self
+    }
+
+    /// Whether the user quit from the stepper.
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.backend.is_some() && self.error.is_none() && !self.quit
+    }
+
+    /// Build a frame with `draw` and show it. After the backend fails once
+    /// nothing more is drawn, and the error comes back from `finish`.
+    pub fn show(&mut self, draw: impl FnOnce() -> Frame) {
+        if !self.is_on() {
+            return;
+        }
+        if let Some(backend) = self.backend.as_deref_mut() {
+            if let Err(e) = backend.show(&draw()) {
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Call once per tick, after showing its frame, with the values the
+    /// stepper can break on. Returns false once the user quits, and the
+    /// simulation should stop.
+    pub fn tick(&mut self, watch: &[(&str, i64)]) -> bool {
+        if let Some(stepper) = &mut self.stepper {
+            self.quit = self.quit || !stepper.tick(watch);
+        }
+        !self.quit
+    }
+
+    pub fn finish(self) -> io::Result<()> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        match self.backend {
+            Some(backend) => backend.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::stepper::Mode;
+
+    fn frame() -> Frame {
+        let mut frame = Frame::new(3, 2, '.').caption("tick 1");
+        frame.put(0, 0, '#');
+        frame.set(2, 1, Cell::coloured('@', Colour::RED));
+        frame.put(5, 5, 'x');
+        frame
+    }
+
+    #[test]
+    fn test_text() {
+        assert_eq!(frame().to_text(), "tick 1\n#..\n..@\n");
+        assert_eq!(frame().get(2, 1), Some(Cell::coloured('@', Colour::RED)));
+        assert_eq!(frame().get(3, 0), None);
+    }
+
+    #[test]
+    fn test_ppm() {
+        let mut out = vec![];
+        frame().write_ppm(&mut out, 2).unwrap();
+        let header = b"P6\n6 4\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let pixels = &out[header.len()..];
+        assert_eq!(pixels.len(), 6 * 4 * 3);
+        assert_eq!(&pixels[..6], &[255; 6]);
+        assert_eq!(&pixels[pixels.len() - 3..], &[220, 50, 47]);
+    }
+
+    #[test]
+    fn test_terminal() {
+        let mut term = Terminal::new(vec![], Duration::ZERO);
+        term.show(&frame()).unwrap();
+        term.show(&frame()).unwrap();
+        term.finish().unwrap();
+        let out = String::from_utf8(term.into_inner()).unwrap();
+        assert_eq!(out.matches(&clear::All.to_string()).count(), 1);
+        assert_eq!(out.matches(&cursor::Goto(1, 1).to_string()).count(), 2);
+        assert!(out.contains("#.."));
+        assert!(out.ends_with(&cursor::Show.to_string()));
+    }
+
+    #[test]
+    fn test_viz() {
+        let mut viz = Viz::off();
+        viz.show(|| unreachable!("frames are not built when off"));
+        assert!(viz.finish().is_ok());
+
+        let mut term = Terminal::new(vec![], Duration::ZERO);
+        let mut viz = Viz::new(&mut term);
+        assert!(viz.is_on());
+        viz.show(frame);
+        viz.finish().unwrap();
+        assert!(!term.into_inner().is_empty());
+    }
+
+    #[test]
+    fn test_quit() {
+        let mut term = Terminal::new(vec![], Duration::ZERO);
+        let stepper = Stepper::new(Mode::Pause, "s\nq\n".as_bytes(), io::sink());
+        let mut viz = Viz::new(&mut term).with_stepper(stepper);
+        let mut ticks = 0;
+        loop {
+            viz.show(frame);
+            if !viz.tick(&[]) {
+                break;
+            }
+            ticks += 1;
+        }
+        assert_eq!(ticks, 1);
+        assert!(!viz.is_on());
+        viz.show(|| unreachable!("frames are not built after quitting"));
+    }
+}